@@ -1,13 +1,9 @@
 //! Simple example.
 //! Demonstrates how to enable a clickable gizmo and attach it to a camera, rotating the camera
-//! through the shortest path to align its Z with the clicked axis. Use LCtrl when clicking to
-//! align the view in the opposite direction.
+//! through the shortest path to align its Z with the clicked axis with the built-in
+//! [SnapViewToAxisPlugin].
 
 use bevy::prelude::*;
-use bevy_easings::EasingState::Play;
-use bevy_easings::*;
-use std::f32::consts::PI;
-use std::time::Duration;
 
 use viewport_orientation_gizmo::*;
 
@@ -15,14 +11,16 @@ fn main() {
     App::new()
         .insert_resource(Msaa { samples: 4 })
         .add_plugins(DefaultPlugins)
-        .add_plugin(EasingsPlugin)
         // Enables the system that draws the gizmo
         .add_plugin(ViewportOrientationGizmoPlugin::custom(PluginOptions {
-            gizmo: GIZMO,
+            gizmo: GIZMO.into(),
             ..default()
         }))
+        // Highlights the axis under the cursor
+        .insert_resource(GizmoHighlight::default())
+        // Snaps the camera to the clicked axis
+        .add_plugin(SnapViewToAxisPlugin::new())
         .add_startup_system(setup)
-        .add_system(react_to_clicks)
         .run();
 }
 
@@ -99,48 +97,3 @@ fn setup(
         // This is what enabled rotation tracking on that camera
         .insert(TrackedRotator);
 }
-
-fn react_to_clicks(
-    mut commands: Commands,
-    mut events: EventReader<ClickEvent>,
-    keyboard: Res<Input<KeyCode>>,
-    rotator: Query<(Entity, &mut Transform), With<TrackedRotator>>,
-    animations: Query<&mut EasingComponent<Transform>, With<TrackedRotator>>,
-) {
-    const ANIM_DURATION: Duration = Duration::from_millis(1000);
-
-    let dir = if keyboard.pressed(KeyCode::LControl) {
-        1.
-    } else {
-        -1.
-    };
-
-    let (entity, rotator) = rotator.single();
-    for event in events.iter() {
-        if let Some(axis) = event.0 {
-            if let Ok(current_anim) = animations.get(entity) {
-                if current_anim.state == Play {
-                    // Wait for animations to finish before allowing another rotation
-                    return;
-                } else {
-                    commands
-                        .entity(entity)
-                        .remove::<EasingComponent<Transform>>();
-                }
-            }
-
-            // Align camera Z with selected direction
-            let rot_axis = dir * Vec3::from(axis).cross(rotator.local_z());
-            commands.entity(entity).insert(
-                rotator.ease_to(
-                    Transform::identity()
-                        .with_rotation(Quat::from_axis_angle(rot_axis, PI / 2.) * rotator.rotation),
-                    EaseFunction::ExponentialOut,
-                    EasingType::Once {
-                        duration: ANIM_DURATION,
-                    },
-                ),
-            );
-        }
-    }
-}
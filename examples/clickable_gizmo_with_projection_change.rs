@@ -1,6 +1,6 @@
 //! Advanced example.
 //! Demonstrates how to enable a clickable gizmo and attach it to a camera, rotating the camera
-//! through the shortest path to align its Z with the clicked axis. Use LCtrl when clicking to
+//! through the shortest path to align its Z with the clicked axis. Hold Ctrl when clicking to
 //! align the view in the opposite direction.
 //! Click the middle cube to switch from orthographic to perspective projection.
 
@@ -18,7 +18,7 @@ fn main() {
         .add_plugin(EasingsPlugin)
         // Enables the system that draws the gizmo
         .add_plugin(ViewportOrientationGizmoPlugin::custom(PluginOptions {
-            gizmo: my_gizmo,
+            gizmo: my_gizmo.into(),
             size: 128,
             ..default()
         }))
@@ -109,7 +109,6 @@ struct MainCamera;
 fn react_to_clicks(
     mut commands: Commands,
     mut events: EventReader<ClickEvent>,
-    keyboard: Res<Input<KeyCode>>,
     rotator: Query<(Entity, &mut Transform), With<TrackedRotator>>,
     animations: Query<&mut EasingComponent<Transform>, With<TrackedRotator>>,
     camera: Query<
@@ -124,15 +123,14 @@ fn react_to_clicks(
 ) {
     const ANIM_DURATION: Duration = Duration::from_millis(1000);
 
-    let dir = if keyboard.pressed(KeyCode::LControl) {
-        1.
-    } else {
-        -1.
-    };
-
     let (entity, rotator) = rotator.single();
     for event in events.iter() {
-        if let Some(axis) = event.0 {
+        if event.button != MouseButton::Left {
+            continue;
+        }
+
+        let dir = if event.modifiers.control { 1. } else { -1. };
+        if let Some(axis) = event.axis {
             if let Ok(current_anim) = animations.get(entity) {
                 if current_anim.state == EasingState::Play {
                     // Wait for animations to finish before allowing another rotation
@@ -67,7 +67,7 @@ fn main() {
         .add_plugin(UnrealCameraPlugin::default())
         // Enables my_first_gizmo
         .add_plugin(ViewportOrientationGizmoPlugin::custom(PluginOptions {
-            gizmo: my_first_gizmo,
+            gizmo: my_first_gizmo.into(),
             ..default()
         }))
         .add_startup_system(setup)
@@ -0,0 +1,62 @@
+//! Demonstrates how to display a clickable gizmo in an egui window instead of the Bevy UI canvas.
+//! Clicking an axis snaps the camera to it, as in the clickable_gizmo example.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext, EguiPlugin};
+
+use viewport_orientation_gizmo::*;
+
+fn main() {
+    App::new()
+        .insert_resource(Msaa { samples: 4 })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(EguiPlugin)
+        // Only renders the gizmo to its texture, displayed by the ui system below
+        .add_plugin(ViewportOrientationGizmoPlugin::custom(PluginOptions {
+            size: 128,
+            gizmo: GIZMO.into(),
+            texture_only: true,
+            ..default()
+        }))
+        .insert_resource(GizmoHighlight::default())
+        .add_plugin(SnapViewToAxisPlugin::new())
+        .add_startup_system(setup)
+        .add_system(ui)
+        .run();
+}
+
+fn ui(mut egui_context: ResMut<EguiContext>, mut gizmos: EguiGizmos) {
+    egui::Window::new("Orientation").show(egui_context.ctx_mut(), |ui| {
+        gizmos.show(ui, GizmoInstance(0));
+    });
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn_bundle(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Cube { size: 2.0 })),
+        material: materials.add(Color::GRAY.into()),
+        ..default()
+    });
+
+    // light
+    commands.spawn_bundle(PointLightBundle {
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+
+    // camera
+    commands
+        .spawn_bundle(TransformBundle::default())
+        .with_children(|parent| {
+            parent.spawn_bundle(PerspectiveCameraBundle {
+                transform: Transform::from_translation(Vec3::new(0.0, 0.0, 10.0))
+                    .looking_at(Vec3::ZERO, Vec3::Y),
+                ..default()
+            });
+        })
+        .insert(TrackedRotator);
+}
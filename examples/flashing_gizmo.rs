@@ -97,7 +97,7 @@ fn main() {
         .add_plugin(UnrealCameraPlugin::default())
         // Enables flashing_gizmo
         .add_plugin(ViewportOrientationGizmoPlugin::custom(PluginOptions {
-            gizmo: flashing_gizmo,
+            gizmo: flashing_gizmo.into(),
             ..default()
         }))
         .add_startup_system(setup)
@@ -0,0 +1,99 @@
+use bevy::{prelude::*, render::view::RenderLayers};
+
+use crate::click_reaction::*;
+
+/// Distance of the spheres from the origin.
+const SPHERE_DISTANCE: f32 = 0.85;
+/// Radius of the spheres.
+const SPHERE_RADIUS: f32 = 0.2;
+/// Half the thickness of the positive axes.
+const AXIS_HALF_THICKNESS: f32 = 0.03;
+
+/// Direction, positive and negative clickable axes, color and faded color of each axis.
+const AXES: [(Vec3, GizmoClickableAxis, GizmoClickableAxis, &str, &str); 3] = [
+    (
+        Vec3::X,
+        GizmoClickableAxis::X,
+        GizmoClickableAxis::XNeg,
+        "b82700",
+        "db937f",
+    ),
+    (
+        Vec3::Y,
+        GizmoClickableAxis::Y,
+        GizmoClickableAxis::YNeg,
+        "5d9900",
+        "aecc7f",
+    ),
+    (
+        Vec3::Z,
+        GizmoClickableAxis::Z,
+        GizmoClickableAxis::ZNeg,
+        "2e78e4",
+        "96bbf1",
+    ),
+];
+
+fn blender_gizmo(
+    layers: RenderLayers,
+    commands: &mut ChildBuilder,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    let sphere = meshes.add(Mesh::from(shape::Icosphere {
+        radius: SPHERE_RADIUS,
+        subdivisions: 3,
+    }));
+
+    for (direction, positive, negative, color, faded_color) in AXES {
+        let material = materials.add(StandardMaterial {
+            base_color: Color::hex(color).unwrap(),
+            unlit: true,
+            ..default()
+        });
+        let faded_material = materials.add(StandardMaterial {
+            base_color: Color::hex(faded_color).unwrap(),
+            unlit: true,
+            ..default()
+        });
+
+        // Axis line, from the origin to the positive sphere
+        let min = Vec3::splat(-AXIS_HALF_THICKNESS);
+        let max = Vec3::splat(AXIS_HALF_THICKNESS).max(direction * SPHERE_DISTANCE);
+        commands
+            .spawn_bundle(PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Box {
+                    min_x: min.x,
+                    max_x: max.x,
+                    min_y: min.y,
+                    max_y: max.y,
+                    min_z: min.z,
+                    max_z: max.z,
+                })),
+                material: material.clone(),
+                ..default()
+            })
+            .insert(RaycastableGizmo::default())
+            .insert(positive)
+            .insert(layers);
+
+        for (sign, axis, material) in [(1.0, positive, material), (-1.0, negative, faded_material)]
+        {
+            commands
+                .spawn_bundle(PbrBundle {
+                    mesh: sphere.clone(),
+                    material,
+                    transform: Transform::from_translation(direction * sign * SPHERE_DISTANCE),
+                    ..default()
+                })
+                .insert(RaycastableGizmo::default())
+                .insert(axis)
+                .insert(layers);
+        }
+    }
+}
+
+/// Blender-like gizmo: a colored sphere at the end of each positive axis, and a faded sphere on
+/// the opposite side. Every sphere is clickable and reports its signed axis.
+/// Combine it with [AxisLabels][crate::AxisLabels] to name the axes.
+pub static BLENDER_GIZMO: crate::GizmoFn = blender_gizmo;
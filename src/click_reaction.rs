@@ -7,7 +7,12 @@ use bevy_mod_raycast::{
     DefaultRaycastingPlugin, RayCastMesh, RayCastMethod, RayCastSource, RaycastSystem,
 };
 
-use crate::{FirstPassCameraRoot, GizmoUi};
+use std::time::Duration;
+
+use crate::drag::GizmoDragState;
+use crate::{
+    find_instance, update_gizmo_cursor, FirstPassCamera, GizmoCursor, GizmoInstance, HiddenGizmo,
+};
 
 pub(crate) struct ClickReactionPlugin;
 
@@ -16,12 +21,22 @@ impl Plugin for ClickReactionPlugin {
         app.add_plugin(DefaultRaycastingPlugin::<GizmoRaycastSet>::default())
             .add_system_to_stage(
                 CoreStage::PreUpdate,
-                update_raycast_with_cursor.before(RaycastSystem::BuildRays),
+                update_raycast_with_cursor
+                    .after(update_gizmo_cursor)
+                    .before(RaycastSystem::BuildRays),
             )
-            // Make sure this startup runs last, so that the FirstPassCamera is already spawned
+            // Make sure this startup runs last, so that the first FirstPassCameras are already spawned
             .add_startup_system_to_stage(StartupStage::PostStartup, setup)
+            .add_system(setup)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_hovered_part.after(RaycastSystem::UpdateRaycast),
+            )
             .add_system(listen_for_clicks)
-            .add_event::<ClickEvent>();
+            .add_system(highlight_hovered_part)
+            .init_resource::<HoveredGizmoPart>()
+            .add_event::<ClickEvent>()
+            .add_event::<GizmoHoverEvent>();
     }
 }
 
@@ -31,13 +46,30 @@ pub struct GizmoRaycastSet;
 /// Use this to add clickable parts to your gizmo
 pub type RaycastableGizmo = RayCastMesh<GizmoRaycastSet>;
 
-fn setup(mut commands: Commands, query: Query<&Children, With<FirstPassCameraRoot>>) {
-    if let Ok(e) = query.get_single() {
-        let e = e.get(0).unwrap();
+/// Raycasts from the first pass cameras of the visible gizmos only
+#[allow(clippy::type_complexity)]
+fn setup(
+    mut commands: Commands,
+    query: Query<
+        Entity,
+        (
+            With<FirstPassCamera>,
+            Without<RayCastSource<GizmoRaycastSet>>,
+            Without<HiddenGizmo>,
+        ),
+    >,
+    hidden: Query<Entity, (With<HiddenGizmo>, With<RayCastSource<GizmoRaycastSet>>)>,
+) {
+    for e in query.iter() {
         commands
-            .entity(*e)
+            .entity(e)
             .insert(RayCastSource::<GizmoRaycastSet>::default());
     }
+    for e in hidden.iter() {
+        commands
+            .entity(e)
+            .remove::<RayCastSource<GizmoRaycastSet>>();
+    }
 }
 
 impl From<GizmoClickableAxis> for Vec3 {
@@ -49,12 +81,14 @@ impl From<GizmoClickableAxis> for Vec3 {
             GizmoClickableAxis::XNeg => -Vec3::X,
             GizmoClickableAxis::YNeg => -Vec3::Y,
             GizmoClickableAxis::ZNeg => -Vec3::Z,
+            GizmoClickableAxis::Direction(direction) => direction,
         }
     }
 }
 
 /// Attach this component to the meshes you want to represent clickable axis.
 #[derive(Component, Copy, Clone)]
+#[cfg_attr(feature = "ron-gizmo", derive(serde::Deserialize))]
 pub enum GizmoClickableAxis {
     X,
     Y,
@@ -62,81 +96,297 @@ pub enum GizmoClickableAxis {
     XNeg,
     YNeg,
     ZNeg,
+    /// Any other view direction, such as the edges and corners of a view cube. Does not need to
+    /// be normalized.
+    Direction(Vec3),
 }
 
-/// Event sent when one of the [GizmoClickable] is left-clicked. Contains the [GizmoAxis] that was
-/// clicked.
-pub struct ClickEvent(pub Option<GizmoClickableAxis>, pub Entity);
+impl GizmoClickableAxis {
+    /// Parses "X", "Y", "Z", "-X", "-Y" or "-Z", ignoring case, surrounding whitespace and a
+    /// leading "+".
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        let name = name.strip_prefix('+').unwrap_or(name);
+        match name.to_ascii_uppercase().as_str() {
+            "X" => Some(Self::X),
+            "Y" => Some(Self::Y),
+            "Z" => Some(Self::Z),
+            "-X" => Some(Self::XNeg),
+            "-Y" => Some(Self::YNeg),
+            "-Z" => Some(Self::ZNeg),
+            _ => None,
+        }
+    }
+}
 
-fn listen_for_clicks(
-    mut events: EventWriter<ClickEvent>,
-    mut mouse_button_events: EventReader<MouseButtonInput>,
-    tracked_entities: Query<(Entity, Option<&GizmoClickableAxis>), With<RaycastableGizmo>>,
-    raycast_src: Query<&RayCastSource<GizmoRaycastSet>>,
-) {
-    // Did we receive a left mouse click this frame?
-    let mut left_click = false;
-    for e in mouse_button_events.iter() {
-        if e.button == MouseButton::Left && e.state == ElementState::Released {
-            left_click = true;
-            break;
+/// Maximum delay between two clicks on the same part for them to count as a multiple click.
+const MULTI_CLICK_DELAY: Duration = Duration::from_millis(400);
+
+/// Modifier keys held when a click happened. Left and right keys are not distinguished.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClickModifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub logo: bool,
+}
+
+impl ClickModifiers {
+    fn from_keyboard(keyboard: &Input<KeyCode>) -> Self {
+        Self {
+            shift: keyboard.any_pressed([KeyCode::LShift, KeyCode::RShift]),
+            control: keyboard.any_pressed([KeyCode::LControl, KeyCode::RControl]),
+            alt: keyboard.any_pressed([KeyCode::LAlt, KeyCode::RAlt]),
+            logo: keyboard.any_pressed([KeyCode::LWin, KeyCode::RWin]),
         }
     }
-    if !left_click {
-        return;
+}
+
+/// Event sent when one of the [RaycastableGizmo] parts is clicked, i.e. when a mouse button is
+/// released over it.
+pub struct ClickEvent {
+    /// The [GizmoClickableAxis] of the clicked part, if any.
+    pub axis: Option<GizmoClickableAxis>,
+    /// The clicked part.
+    pub entity: Entity,
+    /// The gizmo the clicked part belongs to.
+    pub instance: GizmoInstance,
+    pub button: MouseButton,
+    pub modifiers: ClickModifiers,
+    /// 1 for a simple click, 2 for a double click, and so on.
+    pub click_count: u32,
+    /// Where the part was hit, in gizmo space.
+    pub hit_position: Vec3,
+    /// The normal of the part surface where it was hit, in gizmo space.
+    pub hit_normal: Vec3,
+}
+
+/// Event sent when the cursor enters or leaves one of the [RaycastableGizmo] parts. Contains the
+/// [GizmoClickableAxis] of the part, if any.
+pub enum GizmoHoverEvent {
+    Enter(Option<GizmoClickableAxis>, Entity),
+    Leave(Option<GizmoClickableAxis>, Entity),
+}
+
+/// Insert this resource to highlight the gizmo part under the cursor. The part gets its own copy
+/// of its material while highlighted.
+#[derive(Clone, Copy)]
+pub enum GizmoHighlight {
+    /// Mixes the base color with the given color, weighted by its alpha.
+    Tint(Color),
+    /// Replaces the emissive color; only visible on lit materials.
+    Emissive(Color),
+}
+
+impl Default for GizmoHighlight {
+    fn default() -> Self {
+        GizmoHighlight::Tint(Color::rgba(1.0, 1.0, 1.0, 0.5))
     }
+}
+
+/// The gizmo part currently under the cursor.
+#[derive(Default)]
+pub(crate) struct HoveredGizmoPart(pub Option<GizmoHit>);
+
+/// Where the cursor hits a gizmo part.
+#[derive(Clone, Copy)]
+pub(crate) struct GizmoHit {
+    pub entity: Entity,
+    pub instance: GizmoInstance,
+    pub position: Vec3,
+    pub normal: Vec3,
+}
+
+/// Marks a highlighted gizmo part, remembering the material to restore.
+#[derive(Component)]
+struct HighlightedGizmoPart(Handle<StandardMaterial>);
+
+fn update_hovered_part(
+    mut hovered: ResMut<HoveredGizmoPart>,
+    mut events: EventWriter<GizmoHoverEvent>,
+    tracked_entities: Query<Option<&GizmoClickableAxis>, With<RaycastableGizmo>>,
+    hierarchy: Query<(Option<&Parent>, Option<&GizmoInstance>)>,
+    raycast_src: Query<(&GizmoInstance, &RayCastSource<GizmoRaycastSet>)>,
+    cursor: Res<GizmoCursor>,
+) {
+    // Are we over one of the gizmo parts?
+    let mut hit = None;
+    for (instance, s) in raycast_src.iter() {
+        // Only the gizmo currently under the cursor can be hovered
+        if cursor.hovered_instance() != Some(*instance) {
+            continue;
+        }
 
-    // Did we click on one of the gizmo parts? If yes, derive which axis it represents
-    let (mut axis, mut entity) = (None, None);
-    for s in raycast_src.iter() {
         if let Some(ls) = s.intersect_list() {
-            if let Some(intersect) = ls.iter().next() {
-                entity = Some(intersect.0);
-                if let Ok(raycastable) = tracked_entities.get(intersect.0) {
-                    if let Some(gizmo_clickable) = raycastable.1 {
-                        axis = Some(*gizmo_clickable);
-                    }
-                }
+            // All gizmos share the same space: skip the parts of the other instances
+            if let Some(intersect) = ls
+                .iter()
+                .find(|(e, _)| find_instance(*e, &hierarchy).as_ref() == Some(instance))
+            {
+                // The gizmo meshes root sits at the origin: world space is gizmo space
+                hit = Some(GizmoHit {
+                    entity: intersect.0,
+                    instance: *instance,
+                    position: intersect.1.position(),
+                    normal: intersect.1.normal(),
+                });
                 break;
             }
         }
     }
 
-    if entity.is_none() {
+    let previous = hovered.0.map(|hit| hit.entity);
+    let current = hit.map(|hit| hit.entity);
+    hovered.0 = hit;
+    if previous == current {
         return;
     }
+    let axis_of = |e: Entity| tracked_entities.get(e).ok().flatten().copied();
+    if let Some(previous) = previous {
+        events.send(GizmoHoverEvent::Leave(axis_of(previous), previous));
+    }
+    if let Some(current) = current {
+        events.send(GizmoHoverEvent::Enter(axis_of(current), current));
+    }
+}
+
+fn highlight_hovered_part(
+    mut commands: Commands,
+    mut events: EventReader<GizmoHoverEvent>,
+    highlight: Option<Res<GizmoHighlight>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut parts: Query<(&mut Handle<StandardMaterial>, Option<&HighlightedGizmoPart>)>,
+) {
+    for event in events.iter() {
+        match *event {
+            GizmoHoverEvent::Enter(_, entity) => {
+                let highlight = match &highlight {
+                    Some(highlight) => **highlight,
+                    None => continue,
+                };
+                if let Ok((mut handle, None)) = parts.get_mut(entity) {
+                    let mut material = match materials.get(&*handle) {
+                        Some(material) => material.clone(),
+                        None => continue,
+                    };
+                    match highlight {
+                        GizmoHighlight::Tint(color) => {
+                            let a = color.a();
+                            let base = Vec4::from(material.base_color);
+                            let tint = Vec4::from(color).truncate().extend(base.w);
+                            material.base_color = Color::from(base.lerp(tint, a));
+                        }
+                        GizmoHighlight::Emissive(color) => material.emissive = color,
+                    }
+                    let original = std::mem::replace(&mut *handle, materials.add(material));
+                    commands
+                        .entity(entity)
+                        .insert(HighlightedGizmoPart(original));
+                }
+            }
+            GizmoHoverEvent::Leave(_, entity) => {
+                if let Ok((mut handle, Some(original))) = parts.get_mut(entity) {
+                    *handle = original.0.clone();
+                    commands.entity(entity).remove::<HighlightedGizmoPart>();
+                }
+            }
+        }
+    }
+}
 
-    events.send(ClickEvent(axis, entity.unwrap()))
+/// The last click, to detect multiple clicks.
+#[derive(Default)]
+struct LastClick {
+    entity: Option<Entity>,
+    button: Option<MouseButton>,
+    time: Duration,
+    count: u32,
 }
 
-fn update_raycast_with_cursor(
-    mut cursor: EventReader<CursorMoved>,
-    mut query: Query<&mut RayCastSource<GizmoRaycastSet>>,
-    gizmo_ui: Query<(&Interaction, &GlobalTransform, &Node), With<GizmoUi>>,
+#[allow(clippy::too_many_arguments)]
+fn listen_for_clicks(
+    mut events: EventWriter<ClickEvent>,
+    mut mouse_button_events: EventReader<MouseButtonInput>,
+    mut last_click: Local<LastClick>,
+    time: Res<Time>,
+    keyboard: Res<Input<KeyCode>>,
+    hovered: Res<HoveredGizmoPart>,
+    drag_state: Res<GizmoDragState>,
+    tracked_entities: Query<Option<&GizmoClickableAxis>, With<RaycastableGizmo>>,
 ) {
-    // Grab the most recent cursor event if it exists:
-    let mut cursor_position = match cursor.iter().last() {
-        Some(cursor_moved) => cursor_moved.position,
-        None => return,
-    };
+    for e in mouse_button_events.iter() {
+        if e.state != ElementState::Released {
+            continue;
+        }
+        // Releasing the button at the end of a drag is not a click
+        if e.button == MouseButton::Left && drag_state.just_ended {
+            continue;
+        }
 
-    let (gizmo_interaction, global_transform, node) = gizmo_ui.single();
+        // Did we click on one of the gizmo parts? If yes, derive which axis it represents
+        let hit = match hovered.0 {
+            Some(hit) => hit,
+            None => continue,
+        };
+        let axis = tracked_entities.get(hit.entity).ok().flatten().copied();
 
-    // Not over the gizmo UI, don't even update raycast source
-    if gizmo_interaction == &Interaction::None {
-        return;
-    };
+        let now = time.time_since_startup();
+        if last_click.entity == Some(hit.entity)
+            && last_click.button == Some(e.button)
+            && now - last_click.time <= MULTI_CLICK_DELAY
+        {
+            last_click.count += 1;
+        } else {
+            last_click.count = 1;
+        }
+        last_click.entity = Some(hit.entity);
+        last_click.button = Some(e.button);
+        last_click.time = now;
 
-    // Get back cursor_position in the size x size rendered texture coordinate system
-    {
-        let position = global_transform.translation;
-        let ui_position = position.truncate();
-        let extents = node.size / 2.0;
-        let min = ui_position - extents;
-        cursor_position -= min;
+        events.send(ClickEvent {
+            axis,
+            entity: hit.entity,
+            instance: hit.instance,
+            button: e.button,
+            modifiers: ClickModifiers::from_keyboard(&keyboard),
+            click_count: last_click.count,
+            hit_position: hit.position,
+            hit_normal: hit.normal,
+        })
     }
+}
 
-    for mut pick_source in &mut query.iter_mut() {
+fn update_raycast_with_cursor(
+    cursor: Res<GizmoCursor>,
+    mut query: Query<(&GizmoInstance, &mut RayCastSource<GizmoRaycastSet>)>,
+) {
+    // Not over a gizmo, don't even update raycast source
+    let (instance, cursor_position) = match cursor.hovered {
+        Some(hovered) => hovered,
+        None => return,
+    };
+
+    for (_, mut pick_source) in query.iter_mut().filter(|(i, _)| **i == instance) {
         pick_source.cast_method = RayCastMethod::Screenspace(cursor_position);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_name_parses_axes() {
+        let axis = |name: &str| GizmoClickableAxis::from_name(name).map(Vec3::from);
+        assert_eq!(axis("X"), Some(Vec3::X));
+        assert_eq!(axis("y"), Some(Vec3::Y));
+        assert_eq!(axis(" +Z "), Some(Vec3::Z));
+        assert_eq!(axis("-x"), Some(-Vec3::X));
+        assert_eq!(axis("-Y"), Some(-Vec3::Y));
+        assert_eq!(axis("-z"), Some(-Vec3::Z));
+        assert!(axis("W").is_none());
+        assert!(axis("--X").is_none());
+        assert!(axis("X.001").is_none());
+        assert!(axis("").is_none());
+    }
+}
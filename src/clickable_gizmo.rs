@@ -58,4 +58,4 @@ gizmo![the_gizmo(meshes, materials):
 ];
 
 /// Default clickable gizmo
-pub static GIZMO: crate::plugin::Gizmo = the_gizmo;
+pub static GIZMO: crate::GizmoFn = the_gizmo;
@@ -55,4 +55,4 @@ gizmo![the_gizmo(meshes, materials):
     }
 ];
 
-pub(crate) static GIZMO: crate::plugin::Gizmo = the_gizmo;
+pub(crate) static GIZMO: crate::GizmoFn = the_gizmo;
@@ -0,0 +1,192 @@
+//! Dragging the gizmo with the mouse, optionally orbiting the tracked rotator.
+
+use bevy::{input::InputSystem, prelude::*, window::WindowId};
+
+use crate::{
+    find_tracked_rotator, tracking_space_rotation, update_gizmo_cursor, DrivesGizmo,
+    FirstPassCameraRoot, GizmoCursor, GizmoInstance, PluginOptions, TrackedRotator,
+};
+
+/// Distance, in logical pixels, the cursor must travel with the button held before a press on the
+/// gizmo becomes a drag.
+const DRAG_THRESHOLD: f32 = 3.0;
+
+/// How cursor motion is turned into a rotation of the [TrackedRotator].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "ron-gizmo", derive(serde::Deserialize))]
+pub enum DragMode {
+    /// Horizontal motion rotates around the world up axis, vertical motion around the view's
+    /// horizontal axis; the horizon always stays level.
+    Turntable,
+    /// Rotates around the view axis perpendicular to the cursor motion; allows rolling the view.
+    Trackball,
+}
+
+/// Enables orbiting the [TrackedRotator] by dragging the gizmo with the left mouse button.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "ron-gizmo", derive(serde::Deserialize))]
+pub struct DragOrbit {
+    pub mode: DragMode,
+    /// Radians per logical pixel of cursor motion.
+    pub sensitivity: f32,
+}
+
+impl DragOrbit {
+    /// Turntable orbit at 0.01 radian per pixel.
+    pub fn turntable() -> Self {
+        Self {
+            mode: DragMode::Turntable,
+            sensitivity: 0.01,
+        }
+    }
+
+    /// Trackball orbit at 0.01 radian per pixel.
+    pub fn trackball() -> Self {
+        Self {
+            mode: DragMode::Trackball,
+            ..Self::turntable()
+        }
+    }
+}
+
+impl Default for DragOrbit {
+    fn default() -> Self {
+        Self::turntable()
+    }
+}
+
+/// Event sent while the gizmo is dragged with the left mouse button. Updates contain the cursor
+/// motion in logical pixels since the last event.
+pub enum GizmoDragEvent {
+    Start(GizmoInstance),
+    Update(GizmoInstance, Vec2),
+    End(GizmoInstance),
+}
+
+pub(crate) struct DragPlugin;
+
+impl Plugin for DragPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GizmoDragState>()
+            .add_event::<GizmoDragEvent>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                track_drags.after(InputSystem).after(update_gizmo_cursor),
+            )
+            .add_system(orbit_on_drag);
+    }
+}
+
+/// The gizmo currently pressed, and whether it is being dragged.
+#[derive(Default)]
+pub(crate) struct GizmoDragState {
+    pressed: Option<GizmoInstance>,
+    dragging: bool,
+    origin: Vec2,
+    cursor: Vec2,
+    /// The window `cursor` is in.
+    window: WindowId,
+    /// Set on the frame a drag ends, so that the button release is not mistaken for a click.
+    pub just_ended: bool,
+}
+
+fn track_drags(
+    mut state: ResMut<GizmoDragState>,
+    mut events: EventWriter<GizmoDragEvent>,
+    mut cursor: EventReader<CursorMoved>,
+    buttons: Res<Input<MouseButton>>,
+    gizmo_cursor: Res<GizmoCursor>,
+) {
+    state.just_ended = false;
+    let previous = state.cursor;
+    for cursor_moved in cursor.iter() {
+        // Once pressed, positions in other windows are in unrelated coordinate spaces
+        if state.pressed.is_none() || cursor_moved.id == state.window {
+            state.cursor = cursor_moved.position;
+            state.window = cursor_moved.id;
+        }
+    }
+
+    if buttons.just_pressed(MouseButton::Left) {
+        if let Some(instance) = gizmo_cursor.hovered_instance() {
+            state.pressed = Some(instance);
+            state.dragging = false;
+            state.origin = state.cursor;
+        }
+    }
+
+    let instance = match state.pressed {
+        Some(instance) => instance,
+        None => return,
+    };
+
+    if !buttons.pressed(MouseButton::Left) {
+        if state.dragging {
+            events.send(GizmoDragEvent::End(instance));
+            state.just_ended = true;
+        }
+        state.pressed = None;
+        state.dragging = false;
+        return;
+    }
+
+    let delta = if state.dragging {
+        state.cursor - previous
+    } else if state.cursor.distance(state.origin) > DRAG_THRESHOLD {
+        state.dragging = true;
+        events.send(GizmoDragEvent::Start(instance));
+        state.cursor - state.origin
+    } else {
+        return;
+    };
+
+    if delta != Vec2::ZERO {
+        events.send(GizmoDragEvent::Update(instance, delta));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn orbit_on_drag(
+    mut events: EventReader<GizmoDragEvent>,
+    gizmos: Query<(&GizmoInstance, &PluginOptions), With<FirstPassCameraRoot>>,
+    rotators: Query<(Entity, Option<&DrivesGizmo>), With<TrackedRotator>>,
+    mut transforms: ParamSet<(Query<(&Transform, Option<&Parent>)>, Query<&mut Transform>)>,
+) {
+    for event in events.iter() {
+        let (instance, delta) = match event {
+            GizmoDragEvent::Update(instance, delta) => (*instance, *delta),
+            _ => continue,
+        };
+        let options = match gizmos.iter().find(|(i, _)| **i == instance) {
+            Some((_, options)) => options,
+            None => continue,
+        };
+        let orbit = match options.drag_orbit {
+            Some(orbit) => orbit,
+            None => continue,
+        };
+        let rotator = match find_tracked_rotator(instance, rotators.iter()) {
+            Some(rotator) => rotator,
+            None => continue,
+        };
+
+        // Rotate in the space the gizmo displays, i.e. possibly relative to the parent rotation
+        let parent_rotation = tracking_space_rotation(rotator, options.tracking, &transforms.p0());
+
+        let mut transforms = transforms.p1();
+        let mut transform = match transforms.get_mut(rotator) {
+            Ok(transform) => transform,
+            Err(_) => continue,
+        };
+        let current = parent_rotation * transform.rotation;
+        let yaw = -delta.x * orbit.sensitivity;
+        let pitch = delta.y * orbit.sensitivity;
+        let rotated = match orbit.mode {
+            DragMode::Turntable => {
+                Quat::from_rotation_y(yaw) * current * Quat::from_rotation_x(pitch)
+            }
+            DragMode::Trackball => current * Quat::from_scaled_axis(Vec3::new(pitch, yaw, 0.0)),
+        };
+        transform.rotation = (parent_rotation.inverse() * rotated).normalize();
+    }
+}
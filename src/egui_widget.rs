@@ -0,0 +1,105 @@
+//! Displaying gizmos in egui panels. Hovering, clicking and dragging them behaves as with the
+//! gizmos displayed in the Bevy UI canvas.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::{egui, EguiContext};
+
+use crate::{GizmoCursor, GizmoInstance, GizmoTexture, PluginOptions};
+
+pub(crate) struct EguiWidgetPlugin;
+
+impl Plugin for EguiWidgetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PreUpdate, register_gizmo_textures);
+    }
+}
+
+/// The egui texture of the [GizmoTexture] of an instance, inserted next to it.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct EguiGizmoTexture(pub egui::TextureId);
+
+/// Does nothing until the `EguiPlugin` of bevy_egui is added.
+#[allow(clippy::type_complexity)]
+fn register_gizmo_textures(
+    mut commands: Commands,
+    egui_context: Option<ResMut<EguiContext>>,
+    textures: Query<
+        (Entity, &GizmoTexture),
+        Or<(Changed<GizmoTexture>, Without<EguiGizmoTexture>)>,
+    >,
+) {
+    let mut egui_context = match egui_context {
+        Some(egui_context) => egui_context,
+        None => return,
+    };
+    for (entity, texture) in textures.iter() {
+        // The texture is kept alive by the plugin, even once its instance is despawned
+        let id = egui_context.add_image(texture.0.clone_weak());
+        commands.entity(entity).insert(EguiGizmoTexture(id));
+    }
+}
+
+/// Shows gizmos in egui. Requires the `EguiPlugin` of bevy_egui.
+///
+/// Usage, typically with [PluginOptions::texture_only] set:
+/// ```rust,ignore
+/// fn ui(mut egui_context: ResMut<EguiContext>, mut gizmos: EguiGizmos) {
+///     egui::Window::new("View").show(egui_context.ctx_mut(), |ui| {
+///         gizmos.show(ui, GizmoInstance(0));
+///     });
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct EguiGizmos<'w, 's> {
+    gizmos: Query<
+        'w,
+        's,
+        (
+            &'static GizmoInstance,
+            &'static PluginOptions,
+            &'static GizmoTexture,
+            &'static EguiGizmoTexture,
+        ),
+    >,
+    images: Res<'w, Assets<Image>>,
+    cursor: ResMut<'w, GizmoCursor>,
+}
+
+impl<'w, 's> EguiGizmos<'w, 's> {
+    /// Shows the gizmo of `instance`, [PluginOptions::size] points wide. Returns `None` if the
+    /// instance is hidden or not spawned yet.
+    pub fn show(&mut self, ui: &mut egui::Ui, instance: GizmoInstance) -> Option<egui::Response> {
+        let (_, options, texture, egui_texture) =
+            self.gizmos.iter().find(|(i, _, _, _)| **i == instance)?;
+        if !options.visible {
+            return None;
+        }
+
+        let size = egui::Vec2::splat(options.size as f32);
+        let response =
+            ui.add(egui::Image::new(egui_texture.0, size).sense(egui::Sense::click_and_drag()));
+
+        // Picking happens on the next update, in texture pixels with the origin at the bottom left
+        let texture_size = self.images.get(&texture.0).map(|image| image.size());
+        if let (Some(pointer), Some(texture_size)) = (response.hover_pos(), texture_size) {
+            let rect = response.rect;
+            let scale = texture_size / Vec2::new(rect.width(), rect.height());
+            let position = Vec2::new(pointer.x - rect.min.x, rect.max.y - pointer.y);
+            self.cursor.reported = Some((instance, position * scale));
+        }
+        Some(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_without_egui() {
+        let mut app = App::new();
+        app.add_plugin(EguiWidgetPlugin);
+        app.world.spawn().insert(GizmoTexture(Handle::default()));
+        app.update();
+    }
+}
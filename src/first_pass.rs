@@ -1,14 +1,15 @@
 /// Heavily inspired from the [render_to_texture][1] example
 /// [1]: https://github.com/bevyengine/bevy/blob/main/examples/3d/render_to_texture.rs
-use crate::FirstPassCamera;
+use crate::{FirstPassCamera, GizmoOverlay, HiddenGizmo};
 use bevy::{
     core_pipeline::{draw_3d_graph, node, AlphaMask3d, Opaque3d, Transparent3d},
     prelude::*,
     render::{
-        camera::{ActiveCamera, CameraTypePlugin},
+        camera::{Camera, ExtractedCamera},
         render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext, SlotValue},
         render_phase::RenderPhase,
         renderer::RenderContext,
+        view::{ExtractedView, VisibleEntities},
         RenderApp, RenderStage,
     },
 };
@@ -18,12 +19,10 @@ pub const FIRST_PASS_DRIVER: &str = "viewport_ref_frame__first_pass_driver";
 
 /// Initializes rendering graph to account for first pass
 pub fn init_app_rendering(app: &mut App) {
-    app.add_plugin(CameraTypePlugin::<FirstPassCamera>::default());
-
     let render_app = app.sub_app_mut(RenderApp);
     let driver = FirstPassCameraDriver::new(&mut render_app.world);
-    // This will add 3D render phases for the new camera.
-    render_app.add_system_to_stage(RenderStage::Extract, extract_first_pass_camera_phases);
+    // This will extract every first pass camera along with its 3D render phases.
+    render_app.add_system_to_stage(RenderStage::Extract, extract_first_pass_cameras);
 
     let mut graph = render_app.world.resource_mut::<RenderGraph>();
 
@@ -44,17 +43,45 @@ pub fn init_app_rendering(app: &mut App) {
         .unwrap();
 }
 
-/// System that adds 3D render phases for FIRST_PASS_CAMERA.
-fn extract_first_pass_camera_phases(
+/// System that extracts every FIRST_PASS_CAMERA of a visible gizmo and adds their 3D render phases.
+/// Bevy's camera type plugin only extracts a single active camera per camera type, which is not
+/// enough when several gizmo instances coexist.
+#[allow(clippy::type_complexity)]
+fn extract_first_pass_cameras(
     mut commands: Commands,
-    active: Res<ActiveCamera<FirstPassCamera>>,
+    windows: Res<Windows>,
+    images: Res<Assets<Image>>,
+    query: Query<
+        (Entity, &Camera, &GlobalTransform, &VisibleEntities),
+        (
+            With<FirstPassCamera>,
+            Without<GizmoOverlay>,
+            Without<HiddenGizmo>,
+        ),
+    >,
 ) {
-    if let Some(entity) = active.get() {
-        commands.get_or_spawn(entity).insert_bundle((
-            RenderPhase::<Opaque3d>::default(),
-            RenderPhase::<AlphaMask3d>::default(),
-            RenderPhase::<Transparent3d>::default(),
-        ));
+    for (entity, camera, transform, visible_entities) in query.iter() {
+        if let Some(size) = camera.target.get_physical_size(&windows, &images) {
+            commands.get_or_spawn(entity).insert_bundle((
+                ExtractedCamera {
+                    target: camera.target.clone(),
+                    physical_size: Some(size),
+                },
+                ExtractedView {
+                    projection: camera.projection_matrix,
+                    transform: *transform,
+                    width: size.x,
+                    height: size.y,
+                    near: camera.near,
+                    far: camera.far,
+                },
+                visible_entities.clone(),
+                FirstPassCamera,
+                RenderPhase::<Opaque3d>::default(),
+                RenderPhase::<AlphaMask3d>::default(),
+                RenderPhase::<Transparent3d>::default(),
+            ));
+        }
     }
 }
 
@@ -1,3 +1,147 @@
+use std::sync::Arc;
+
+use bevy::{prelude::*, render::view::RenderLayers};
+
+#[cfg(feature = "click-reaction")]
+use crate::{GizmoClickableAxis, RaycastableGizmo};
+
+/// Spawns the parts of a gizmo as children of an entity sitting at the origin of the first pass.
+/// Every part must be given `layers` to be rendered.
+///
+/// Implemented by any matching closure or function, including the ones created with the `gizmo!`
+/// macro, so that gizmos can capture their configuration.
+pub trait GizmoSpawner: Send + Sync + 'static {
+    fn spawn(
+        &self,
+        layers: RenderLayers,
+        commands: &mut ChildBuilder,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+    );
+}
+
+impl<F> GizmoSpawner for F
+where
+    F: Fn(RenderLayers, &mut ChildBuilder, &mut Assets<Mesh>, &mut Assets<StandardMaterial>)
+        + Send
+        + Sync
+        + 'static,
+{
+    fn spawn(
+        &self,
+        layers: RenderLayers,
+        commands: &mut ChildBuilder,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+    ) {
+        self(layers, commands, meshes, materials)
+    }
+}
+
+/// A gizmo, as created with the `gizmo!` macro.
+pub type GizmoFn =
+    fn(RenderLayers, &mut ChildBuilder, &mut Assets<Mesh>, &mut Assets<StandardMaterial>);
+
+/// The gizmo displayed by an instance, shared between the clones of its [PluginOptions][crate::PluginOptions].
+/// Build it from any [GizmoSpawner] with `.into()` or [Gizmo::new].
+#[derive(Clone)]
+pub struct Gizmo(Arc<dyn GizmoSpawner>);
+
+impl Gizmo {
+    pub fn new(spawner: impl GizmoSpawner) -> Self {
+        Self(Arc::new(spawner))
+    }
+
+    pub(crate) fn spawn(
+        &self,
+        layers: RenderLayers,
+        commands: &mut ChildBuilder,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+    ) {
+        self.0.spawn(layers, commands, meshes, materials)
+    }
+}
+
+impl<S: GizmoSpawner> From<S> for Gizmo {
+    fn from(spawner: S) -> Self {
+        Self::new(spawner)
+    }
+}
+
+/// Three boxes along the X, Y and Z axes, configurable at runtime.
+#[derive(Clone)]
+pub struct AxesGizmo {
+    /// Length of each axis, from the origin.
+    pub length: f32,
+    pub thickness: f32,
+    /// Colors of the X, Y and Z axes.
+    pub colors: [Color; 3],
+    /// Makes each axis report its [GizmoClickableAxis] when clicked.
+    #[cfg(feature = "click-reaction")]
+    pub clickable: bool,
+}
+
+impl Default for AxesGizmo {
+    /// Looks like the default gizmo.
+    fn default() -> Self {
+        Self {
+            length: 1.0,
+            thickness: 0.05,
+            colors: [
+                Color::hex("b82700").unwrap(),
+                Color::hex("5d9900").unwrap(),
+                Color::hex("2e78e4").unwrap(),
+            ],
+            #[cfg(feature = "click-reaction")]
+            clickable: false,
+        }
+    }
+}
+
+impl GizmoSpawner for AxesGizmo {
+    fn spawn(
+        &self,
+        layers: RenderLayers,
+        commands: &mut ChildBuilder,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+    ) {
+        for (i, color) in self.colors.into_iter().enumerate() {
+            let axis = Vec3::AXES[i];
+            let max = Vec3::splat(self.thickness).max(axis * self.length);
+            let mut part = commands.spawn_bundle(PbrBundle {
+                mesh: meshes.add(Mesh::from(shape::Box {
+                    min_x: 0.0,
+                    min_y: 0.0,
+                    min_z: 0.0,
+                    max_x: max.x,
+                    max_y: max.y,
+                    max_z: max.z,
+                })),
+                material: materials.add(StandardMaterial {
+                    base_color: color,
+                    unlit: true,
+                    ..default()
+                }),
+                ..default()
+            });
+            part.insert(layers);
+
+            #[cfg(feature = "click-reaction")]
+            if self.clickable {
+                part.insert(RaycastableGizmo::default()).insert(
+                    [
+                        GizmoClickableAxis::X,
+                        GizmoClickableAxis::Y,
+                        GizmoClickableAxis::Z,
+                    ][i],
+                );
+            }
+        }
+    }
+}
+
 /// Allows for customizing the object rendered as a gizmo.
 ///
 /// Usage:
@@ -9,7 +153,7 @@
 /// ];
 /// // ...
 ///     .add_plugin(ViewportOrientationGizmoPlugin::custom(PluginOptions {
-///         gizmo: some_gizmo_name,
+///         gizmo: some_gizmo_name.into(),
 ///         ..default()
 ///     }))
 /// // ...
@@ -20,9 +164,9 @@ macro_rules! gizmo {
         #[allow(unused_variables, unused_mut, unused_parens)]
         fn $gizmo_name(
             layers: bevy::render::view::RenderLayers,
-            commands: &mut bevy::prelude::Commands,
-            mut $meshes: bevy::prelude::ResMut<bevy::prelude::Assets<bevy::prelude::Mesh>>,
-            mut $materials: bevy::prelude::ResMut<bevy::prelude::Assets<bevy::prelude::StandardMaterial>>,
+            commands: &mut bevy::prelude::ChildBuilder,
+            $meshes: &mut bevy::prelude::Assets<bevy::prelude::Mesh>,
+            $materials: &mut bevy::prelude::Assets<bevy::prelude::StandardMaterial>,
         ) {$(
             commands
                 .spawn_bundle($bundle)
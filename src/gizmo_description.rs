@@ -0,0 +1,296 @@
+//! Gizmos and plugin options described in RON assets, reloaded when the assets change.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    render::view::RenderLayers,
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    CanvasLocation, DragOrbit, GizmoInstance, GizmoSpawner, PluginOptions, RespawnGizmo,
+    RotationTracking,
+};
+
+/// [PluginOptions] and gizmo parts, loaded from `.gizmo.ron` files.
+///
+/// To use it, spawn an entity with a [GizmoInstance] and the `Handle<GizmoDescription>` to use;
+/// the instance is spawned once the description is loaded, and respawned whenever it changes.
+/// Options not covered by the description, such as the axis labels, are kept on reload.
+///
+/// ```ron
+/// (
+///     size: 96,
+///     location: TopRight,
+///     tracking: Global,
+///     drag_orbit: Some((mode: Turntable, sensitivity: 0.01)),
+///     parts: [
+///         (shape: Box(min: (0.0, 0.0, 0.0), max: (1.0, 0.1, 0.1)), color: "b82700", axis: Some(X)),
+///         (shape: Sphere(radius: 0.15), translation: (0.0, 1.0, 0.0), color: "5d9900"),
+///     ],
+/// )
+/// ```
+#[derive(Deserialize, TypeUuid, Clone)]
+#[uuid = "c164f1f9-aaeb-4957-9d6d-ca517f85aad6"]
+#[serde(default)]
+pub struct GizmoDescription {
+    pub size: u32,
+    pub location: LocationDescription,
+    pub tracking: RotationTracking,
+    pub drag_orbit: Option<DragOrbit>,
+    /// The parts making the gizmo; the default gizmo is displayed if empty.
+    pub parts: Vec<GizmoPartDescription>,
+}
+
+impl Default for GizmoDescription {
+    fn default() -> Self {
+        Self {
+            size: 64,
+            location: LocationDescription::BottomLeft,
+            tracking: RotationTracking::Local,
+            drag_orbit: None,
+            parts: Vec::new(),
+        }
+    }
+}
+
+impl GizmoDescription {
+    /// The options described, completed by `previous` ones if any.
+    pub fn plugin_options(&self, previous: Option<&PluginOptions>) -> PluginOptions {
+        let defaults = previous.cloned().unwrap_or_default();
+        PluginOptions {
+            size: self.size,
+            location: self.location.clone().into(),
+            gizmo: if self.parts.is_empty() {
+                PluginOptions::default().gizmo
+            } else {
+                self.clone().into()
+            },
+            tracking: self.tracking,
+            drag_orbit: self.drag_orbit,
+            ..defaults
+        }
+    }
+}
+
+impl GizmoSpawner for GizmoDescription {
+    fn spawn(
+        &self,
+        layers: RenderLayers,
+        commands: &mut ChildBuilder,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+    ) {
+        for part in &self.parts {
+            #[allow(unused_mut, unused_variables)]
+            let mut entity = commands.spawn_bundle(PbrBundle {
+                mesh: meshes.add(part.shape.mesh()),
+                material: materials.add(StandardMaterial {
+                    base_color: part.color,
+                    unlit: part.unlit,
+                    ..default()
+                }),
+                transform: Transform::from_translation(part.translation),
+                ..default()
+            });
+            entity.insert(layers);
+
+            #[cfg(feature = "click-reaction")]
+            if part.clickable || part.axis.is_some() {
+                entity.insert(crate::RaycastableGizmo::default());
+            }
+            #[cfg(feature = "click-reaction")]
+            if let Some(axis) = part.axis {
+                entity.insert(axis);
+            }
+        }
+    }
+}
+
+/// Mirrors [CanvasLocation].
+#[derive(Deserialize, Clone)]
+pub enum LocationDescription {
+    TopLeft,
+    TopRight,
+    BottomRight,
+    BottomLeft,
+    Custom {
+        #[serde(default)]
+        left: Val,
+        #[serde(default)]
+        right: Val,
+        #[serde(default)]
+        top: Val,
+        #[serde(default)]
+        bottom: Val,
+    },
+}
+
+impl From<LocationDescription> for CanvasLocation {
+    fn from(location: LocationDescription) -> Self {
+        match location {
+            LocationDescription::TopLeft => CanvasLocation::TopLeft,
+            LocationDescription::TopRight => CanvasLocation::TopRight,
+            LocationDescription::BottomRight => CanvasLocation::BottomRight,
+            LocationDescription::BottomLeft => CanvasLocation::BottomLeft,
+            LocationDescription::Custom {
+                left,
+                right,
+                top,
+                bottom,
+            } => CanvasLocation::Custom(Rect {
+                left,
+                right,
+                top,
+                bottom,
+            }),
+        }
+    }
+}
+
+/// A part of a described gizmo. Parts are unlit by default.
+///
+/// With the `click-reaction` feature, parts given an `axis` or set `clickable` are clickable;
+/// these fields are ignored otherwise.
+#[derive(Deserialize, Clone)]
+pub struct GizmoPartDescription {
+    pub shape: ShapeDescription,
+    /// Hexadecimal color, e.g. "b82700".
+    #[serde(deserialize_with = "hex_color")]
+    pub color: Color,
+    #[serde(default)]
+    pub translation: Vec3,
+    #[serde(default = "unlit_by_default")]
+    pub unlit: bool,
+    #[cfg(feature = "click-reaction")]
+    #[serde(default)]
+    pub axis: Option<crate::GizmoClickableAxis>,
+    #[cfg(feature = "click-reaction")]
+    #[serde(default)]
+    pub clickable: bool,
+}
+
+/// Primitive shapes available to described gizmo parts.
+#[derive(Deserialize, Clone)]
+pub enum ShapeDescription {
+    Box { min: Vec3, max: Vec3 },
+    Cube { size: f32 },
+    Sphere { radius: f32 },
+    Capsule { radius: f32, depth: f32 },
+    Torus { radius: f32, ring_radius: f32 },
+}
+
+impl ShapeDescription {
+    fn mesh(&self) -> Mesh {
+        match *self {
+            ShapeDescription::Box { min, max } => Mesh::from(shape::Box {
+                min_x: min.x,
+                max_x: max.x,
+                min_y: min.y,
+                max_y: max.y,
+                min_z: min.z,
+                max_z: max.z,
+            }),
+            ShapeDescription::Cube { size } => Mesh::from(shape::Cube { size }),
+            ShapeDescription::Sphere { radius } => Mesh::from(shape::Icosphere {
+                radius,
+                subdivisions: 3,
+            }),
+            ShapeDescription::Capsule { radius, depth } => Mesh::from(shape::Capsule {
+                radius,
+                depth,
+                ..default()
+            }),
+            ShapeDescription::Torus {
+                radius,
+                ring_radius,
+            } => Mesh::from(shape::Torus {
+                radius,
+                ring_radius,
+                ..default()
+            }),
+        }
+    }
+}
+
+fn hex_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    Color::hex(&hex)
+        .map_err(|e| serde::de::Error::custom(format!("invalid color \"{}\": {:?}", hex, e)))
+}
+
+fn unlit_by_default() -> bool {
+    true
+}
+
+#[derive(Default)]
+struct GizmoDescriptionLoader;
+
+impl AssetLoader for GizmoDescriptionLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let description: GizmoDescription = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(description));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["gizmo.ron"]
+    }
+}
+
+pub(crate) struct GizmoDescriptionPlugin;
+
+impl Plugin for GizmoDescriptionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<GizmoDescription>()
+            .init_asset_loader::<GizmoDescriptionLoader>()
+            .add_system(apply_gizmo_descriptions);
+    }
+}
+
+/// Gives instances their options once their description is loaded, and respawns them when it
+/// changes.
+fn apply_gizmo_descriptions(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<GizmoDescription>>,
+    descriptions: Res<Assets<GizmoDescription>>,
+    instances: Query<(
+        Entity,
+        &GizmoInstance,
+        &Handle<GizmoDescription>,
+        Option<&PluginOptions>,
+    )>,
+) {
+    let modified: Vec<&Handle<GizmoDescription>> = events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { handle } => Some(handle),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, instance, handle, options) in instances.iter() {
+        if options.is_some() && !modified.contains(&handle) {
+            continue;
+        }
+        let description = match descriptions.get(handle) {
+            Some(description) => description,
+            // Not loaded yet
+            None => continue,
+        };
+        commands
+            .entity(entity)
+            .insert(description.plugin_options(options));
+        if options.is_some() {
+            commands.add(RespawnGizmo(*instance));
+        }
+    }
+}
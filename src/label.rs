@@ -0,0 +1,271 @@
+//! Text labels rendered as part of a gizmo.
+
+use ab_glyph::{point, Font as _, FontArc, Glyph, ScaleFont};
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    transform::TransformSystem,
+};
+
+use crate::{find_instance, update_1st_pass_camera_transform, FirstPassCameraRoot, GizmoInstance};
+
+/// Height, in pixels, at which label texts are rasterized.
+const LABEL_RESOLUTION: f32 = 64.0;
+
+/// Font used by the [GizmoLabel]s which do not specify one. Labels are not displayed until a font
+/// is available and loaded.
+pub struct GizmoFont(pub Handle<Font>);
+
+/// Text drawn on a quad facing +Z, part of a gizmo. The quad mesh and its texture are generated
+/// once the font is loaded.
+#[derive(Component, Clone)]
+pub struct GizmoLabel {
+    pub text: String,
+    pub color: Color,
+    /// Height of the text in gizmo space; its width follows the text.
+    pub height: f32,
+    /// Falls back to [GizmoFont] if `None`.
+    pub font: Option<Handle<Font>>,
+}
+
+impl GizmoLabel {
+    /// A black label 0.25 units high, using the [GizmoFont].
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            color: Color::BLACK,
+            height: 0.25,
+            font: None,
+        }
+    }
+}
+
+impl Default for GizmoLabel {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+/// Use this in your gizmo to spawn a [GizmoLabel].
+#[derive(Bundle, Clone, Default)]
+pub struct GizmoLabelBundle {
+    pub label: GizmoLabel,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+}
+
+/// Built-in labels placed at the tip of each axis of a gizmo; see [PluginOptions::axis_labels][crate::PluginOptions::axis_labels].
+/// They always face the gizmo camera.
+#[derive(Clone)]
+pub struct AxisLabels {
+    /// Texts of the X, Y and Z labels.
+    pub texts: [String; 3],
+    /// Texts of the -X, -Y and -Z labels; these are not displayed if `None`.
+    pub negative_texts: Option<[String; 3]>,
+    /// Colors of the X, Y and Z labels, also used by their negative counterparts.
+    pub colors: [Color; 3],
+    /// Height of the texts in gizmo space.
+    pub height: f32,
+    /// Distance of the labels from the gizmo origin.
+    pub distance: f32,
+    /// Falls back to [GizmoFont] if `None`.
+    pub font: Option<Handle<Font>>,
+}
+
+impl AxisLabels {
+    /// "X", "Y" and "Z" labels in the colors of the default gizmo.
+    pub fn new() -> Self {
+        Self {
+            texts: ["X", "Y", "Z"].map(String::from),
+            negative_texts: None,
+            colors: [
+                Color::hex("b82700").unwrap(),
+                Color::hex("5d9900").unwrap(),
+                Color::hex("2e78e4").unwrap(),
+            ],
+            height: 0.3,
+            distance: 1.2,
+            font: None,
+        }
+    }
+
+    /// Same as [AxisLabels::new], with "-X", "-Y" and "-Z" labels as well.
+    pub fn with_negative() -> Self {
+        Self {
+            negative_texts: Some(["-X", "-Y", "-Z"].map(String::from)),
+            ..Self::new()
+        }
+    }
+
+    /// The labels to spawn, with their position in gizmo space.
+    pub(crate) fn labels(&self) -> impl Iterator<Item = (GizmoLabel, Vec3)> + '_ {
+        let axes = [Vec3::X, Vec3::Y, Vec3::Z];
+        let positive = self.texts.iter().zip(axes).zip(self.colors);
+        let negative = self
+            .negative_texts
+            .iter()
+            .flat_map(move |texts| texts.iter().zip(axes.map(|axis| -axis)).zip(self.colors));
+        positive.chain(negative).map(move |((text, axis), color)| {
+            let label = GizmoLabel {
+                text: text.clone(),
+                color,
+                height: self.height,
+                font: self.font.clone(),
+            };
+            (label, axis * self.distance)
+        })
+    }
+}
+
+impl Default for AxisLabels {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Makes a gizmo part always face the gizmo camera, e.g. a [GizmoLabel] which should stay readable
+/// whatever the orientation of the gizmo.
+#[derive(Component, Clone, Copy, Default)]
+pub struct GizmoBillboard;
+
+pub(crate) struct LabelPlugin;
+
+impl Plugin for LabelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(draw_gizmo_labels).add_system_to_stage(
+            CoreStage::PostUpdate,
+            face_gizmo_camera
+                .after(update_1st_pass_camera_transform)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Marks the labels whose text has been rasterized
+#[derive(Component)]
+struct DrawnGizmoLabel;
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn draw_gizmo_labels(
+    mut commands: Commands,
+    gizmo_font: Option<Res<GizmoFont>>,
+    fonts: Res<Assets<Font>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    labels: Query<(Entity, &GizmoLabel), Or<(Changed<GizmoLabel>, Without<DrawnGizmoLabel>)>>,
+    mut warned_no_font: Local<bool>,
+) {
+    for (entity, label) in labels.iter() {
+        let handle = match label
+            .font
+            .as_ref()
+            .or_else(|| gizmo_font.as_ref().map(|f| &f.0))
+        {
+            Some(handle) => handle,
+            None => {
+                if !*warned_no_font {
+                    warn!(
+                        "Gizmo labels need a font: insert a GizmoFont resource or set their font"
+                    );
+                    *warned_no_font = true;
+                }
+                continue;
+            }
+        };
+        let font = match fonts.get(handle) {
+            Some(font) => font,
+            // Try again next frame, the font might not be loaded yet
+            None => continue,
+        };
+
+        let image = rasterize(&label.text, &font.font);
+        let aspect = image.texture_descriptor.size.width as f32
+            / image.texture_descriptor.size.height as f32;
+
+        commands
+            .entity(entity)
+            .insert(meshes.add(Mesh::from(shape::Quad::new(Vec2::new(
+                label.height * aspect,
+                label.height,
+            )))))
+            .insert(materials.add(StandardMaterial {
+                base_color: label.color,
+                base_color_texture: Some(images.add(image)),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }))
+            .insert(DrawnGizmoLabel);
+    }
+}
+
+/// Gives billboards the rotation of the first pass camera of their instance. This assumes the
+/// billboard ancestors are not rotated, which is the case of the gizmo mesh root.
+#[allow(clippy::type_complexity)]
+fn face_gizmo_camera(
+    cameras: Query<(&GizmoInstance, &Transform), With<FirstPassCameraRoot>>,
+    hierarchy: Query<(Option<&Parent>, Option<&GizmoInstance>)>,
+    mut billboards: Query<
+        (Entity, &mut Transform),
+        (With<GizmoBillboard>, Without<FirstPassCameraRoot>),
+    >,
+) {
+    for (entity, mut transform) in billboards.iter_mut() {
+        let instance = match find_instance(entity, &hierarchy) {
+            Some(instance) => instance,
+            None => continue,
+        };
+        if let Some((_, camera)) = cameras.iter().find(|(i, _)| **i == instance) {
+            transform.rotation = camera.rotation;
+        }
+    }
+}
+
+/// Rasterizes `text` on a single line, white on a transparent background.
+fn rasterize(text: &str, font: &FontArc) -> Image {
+    let font = font.as_scaled(LABEL_RESOLUTION);
+
+    let mut glyphs: Vec<Glyph> = Vec::new();
+    let mut caret = 0.0;
+    for c in text.chars() {
+        let id = font.glyph_id(c);
+        if let Some(previous) = glyphs.last() {
+            caret += font.kern(previous.id, id);
+        }
+        glyphs.push(id.with_scale_and_position(font.scale(), point(caret, font.ascent())));
+        caret += font.h_advance(id);
+    }
+
+    let width = (caret.ceil() as u32).max(1);
+    let height = (font.height().ceil() as u32).max(1);
+    let mut data = vec![0u8; (width * height * 4) as usize];
+    for glyph in glyphs {
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|x, y, coverage| {
+                let x = x as i32 + bounds.min.x as i32;
+                let y = y as i32 + bounds.min.y as i32;
+                if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+                    return;
+                }
+                let i = ((y as u32 * width + x as u32) * 4) as usize;
+                data[i..i + 3].fill(255);
+                data[i + 3] = data[i + 3].max((coverage * 255.0) as u8);
+            });
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
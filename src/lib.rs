@@ -1,18 +1,47 @@
 #![doc = include_str!("../README.MD")]
 
+#[cfg(feature = "click-reaction")]
+pub use blender_gizmo::*;
 #[cfg(feature = "click-reaction")]
 pub use click_reaction::*;
 #[cfg(feature = "click-reaction")]
 pub use clickable_gizmo::*;
+pub use drag::*;
+#[cfg(feature = "egui-widget")]
+pub use egui_widget::*;
 pub use gizmo::*;
+#[cfg(feature = "ron-gizmo")]
+pub use gizmo_description::*;
+pub use label::*;
 pub use plugin::*;
+pub use scene_gizmo::*;
+#[cfg(feature = "click-reaction")]
+pub use snap_view::*;
+#[cfg(feature = "click-reaction")]
+pub use view_cube::*;
 
 mod default_gizmo;
+mod drag;
 mod first_pass;
 mod gizmo;
+mod label;
+mod overlay;
 mod plugin;
+mod scene_gizmo;
 
+#[cfg(feature = "click-reaction")]
+mod blender_gizmo;
 #[cfg(feature = "click-reaction")]
 mod click_reaction;
 #[cfg(feature = "click-reaction")]
 mod clickable_gizmo;
+#[cfg(feature = "click-reaction")]
+mod snap_view;
+#[cfg(feature = "click-reaction")]
+mod view_cube;
+
+#[cfg(feature = "ron-gizmo")]
+mod gizmo_description;
+
+#[cfg(feature = "egui-widget")]
+mod egui_widget;
@@ -0,0 +1,265 @@
+//! Overlay mode: the gizmo is drawn straight into a corner viewport of the main 3D camera's
+//! target, after its main pass, instead of going through a texture and the UI.
+use crate::{FirstPassCameraRoot, GizmoInstance, GizmoOverlay, HiddenGizmo, PluginOptions};
+use bevy::{
+    core_pipeline::{node, AlphaMask3d, Opaque3d, Transparent3d},
+    prelude::*,
+    render::{
+        camera::{camera_system, ActiveCamera, Camera, Camera3d, CameraProjection, RenderTarget},
+        primitives::Frustum,
+        render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext},
+        render_phase::{DrawFunctions, PhaseItem, RenderPhase, TrackedRenderPass},
+        render_resource::{
+            LoadOp, Operations, RenderPassDepthStencilAttachment, RenderPassDescriptor,
+        },
+        renderer::RenderContext,
+        view::{ExtractedView, ViewDepthTexture, ViewTarget, VisibilitySystems, VisibleEntities},
+        RenderApp, RenderStage,
+    },
+};
+
+/// The name of the node drawing the overlay gizmos.
+pub const OVERLAY_PASS_DRIVER: &str = "viewport_ref_frame__overlay_pass_driver";
+
+pub(crate) struct OverlayPlugin;
+
+impl Plugin for OverlayPlugin {
+    fn build(&self, app: &mut App) {
+        // Runs after Bevy fits the projection and the frustum to the target, before culling
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            update_overlay_cameras
+                .after(camera_system::<PerspectiveProjection>)
+                .after(VisibilitySystems::UpdatePerspectiveFrusta)
+                .before(VisibilitySystems::CheckVisibility),
+        );
+
+        let render_app = app.sub_app_mut(RenderApp);
+        let driver = OverlayPassDriver::new(&mut render_app.world);
+        render_app.add_system_to_stage(RenderStage::Extract, extract_overlay_cameras);
+
+        let mut graph = render_app.world.resource_mut::<RenderGraph>();
+        graph.add_node(OVERLAY_PASS_DRIVER, driver);
+
+        // Draw over the main pass, under the UI: MAIN_PASS_DRIVER -> OVERLAY_PASS_DRIVER -> UI
+        graph
+            .add_node_edge(node::MAIN_PASS_DRIVER, OVERLAY_PASS_DRIVER)
+            .unwrap();
+        if graph.get_node_state(bevy::ui::node::UI_PASS_DRIVER).is_ok() {
+            graph
+                .add_node_edge(OVERLAY_PASS_DRIVER, bevy::ui::node::UI_PASS_DRIVER)
+                .unwrap();
+        }
+    }
+}
+
+/// Gives the overlay cameras the target of the main 3D camera, so that picking works in its
+/// window, and a square projection matching their viewport.
+///
+/// Their [PerspectiveProjection] is left as fitted to the target by Bevy: changing it would make
+/// Bevy fit it again on the next update. Only the projection matrix and the frustum derived from
+/// it are made square.
+#[allow(clippy::type_complexity)]
+fn update_overlay_cameras(
+    active_3d: Res<ActiveCamera<Camera3d>>,
+    cameras: Query<&Camera, Without<GizmoOverlay>>,
+    mut overlays: Query<
+        (
+            &mut Camera,
+            &PerspectiveProjection,
+            &GlobalTransform,
+            &mut Frustum,
+        ),
+        With<GizmoOverlay>,
+    >,
+) {
+    let target = active_3d
+        .get()
+        .and_then(|camera| cameras.get(camera).ok())
+        .map(|camera| camera.target.clone());
+    for (mut camera, projection, transform, mut frustum) in overlays.iter_mut() {
+        if let Some(target) = &target {
+            if camera.target != *target {
+                camera.target = target.clone();
+            }
+        }
+        let square = PerspectiveProjection {
+            aspect_ratio: 1.0,
+            ..projection.clone()
+        }
+        .get_projection_matrix();
+        if camera.projection_matrix != square {
+            camera.projection_matrix = square;
+        }
+        *frustum = Frustum::from_view_projection(
+            &(square * transform.compute_matrix().inverse()),
+            &transform.translation,
+            &transform.back(),
+            projection.far,
+        );
+    }
+}
+
+/// Where an overlay gizmo is drawn.
+#[derive(Component)]
+struct ExtractedGizmoOverlay {
+    /// The view of the main 3D camera, whose target is drawn into.
+    main_view: Entity,
+    /// Left, top, width and height, in physical pixels.
+    viewport: [f32; 4],
+}
+
+/// Extracts the overlay cameras of the visible gizmos, as views covering the whole target of the
+/// main 3D camera, so that their depth textures match it.
+#[allow(clippy::type_complexity)]
+fn extract_overlay_cameras(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    images: Res<Assets<Image>>,
+    active_3d: Res<ActiveCamera<Camera3d>>,
+    cameras: Query<&Camera, Without<GizmoOverlay>>,
+    overlays: Query<
+        (
+            Entity,
+            &GizmoInstance,
+            &Camera,
+            &GlobalTransform,
+            &VisibleEntities,
+        ),
+        (With<GizmoOverlay>, Without<HiddenGizmo>),
+    >,
+    options: Query<(&GizmoInstance, &PluginOptions), With<FirstPassCameraRoot>>,
+) {
+    let main_view = match active_3d.get() {
+        Some(main_view) => main_view,
+        None => return,
+    };
+    let target = match cameras.get(main_view) {
+        Ok(camera) => &camera.target,
+        Err(_) => return,
+    };
+    let size = match target.get_physical_size(&windows, &images) {
+        Some(size) => size.as_vec2(),
+        None => return,
+    };
+    let scale_factor = match target {
+        RenderTarget::Window(id) => windows.get(*id).map_or(1.0, |w| w.scale_factor() as f32),
+        RenderTarget::Image(_) => 1.0,
+    };
+
+    for (entity, instance, camera, transform, visible_entities) in overlays.iter() {
+        let plugin_options = match options.iter().find(|(i, _)| *i == instance) {
+            Some((_, plugin_options)) => plugin_options,
+            None => continue,
+        };
+        let (min, extent) = match plugin_options.fitted_overlay_viewport(size, scale_factor) {
+            Some(viewport) => viewport,
+            None => continue,
+        };
+
+        commands.get_or_spawn(entity).insert_bundle((
+            ExtractedView {
+                projection: camera.projection_matrix,
+                transform: *transform,
+                width: size.x as u32,
+                height: size.y as u32,
+                near: camera.near,
+                far: camera.far,
+            },
+            ExtractedGizmoOverlay {
+                main_view,
+                viewport: [min.x, min.y, extent, extent],
+            },
+            visible_entities.clone(),
+            RenderPhase::<Opaque3d>::default(),
+            RenderPhase::<AlphaMask3d>::default(),
+            RenderPhase::<Transparent3d>::default(),
+        ));
+    }
+}
+
+/// A node drawing the 3D phases of the overlay cameras into the target of the main 3D camera.
+#[allow(clippy::type_complexity)]
+struct OverlayPassDriver {
+    query: QueryState<(
+        Entity,
+        &'static ExtractedGizmoOverlay,
+        &'static RenderPhase<Opaque3d>,
+        &'static RenderPhase<AlphaMask3d>,
+        &'static RenderPhase<Transparent3d>,
+        &'static ViewDepthTexture,
+    )>,
+    targets: QueryState<&'static ViewTarget>,
+}
+
+impl OverlayPassDriver {
+    pub fn new(render_world: &mut World) -> Self {
+        Self {
+            query: QueryState::new(render_world),
+            targets: QueryState::new(render_world),
+        }
+    }
+}
+
+impl Node for OverlayPassDriver {
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+        self.targets.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        for (view, overlay, opaque, alpha_mask, transparent, depth) in self.query.iter_manual(world)
+        {
+            let target = match self.targets.get_manual(world, overlay.main_view) {
+                Ok(target) => target,
+                Err(_) => continue,
+            };
+            let pass_descriptor = RenderPassDescriptor {
+                label: Some("gizmo_overlay_pass"),
+                color_attachments: &[target.get_color_attachment(Operations {
+                    load: LoadOp::Load,
+                    store: true,
+                })],
+                // The gizmo has its own depth, cleared so that it is drawn over the scene
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: &depth.view,
+                    depth_ops: Some(Operations {
+                        load: LoadOp::Clear(0.0),
+                        store: false,
+                    }),
+                    stencil_ops: None,
+                }),
+            };
+            let render_pass = render_context
+                .command_encoder
+                .begin_render_pass(&pass_descriptor);
+            let mut tracked_pass = TrackedRenderPass::new(render_pass);
+            let [x, y, width, height] = overlay.viewport;
+            tracked_pass.set_viewport(x, y, width, height, 0.0, 1.0);
+
+            // Opaque and alpha mask front-to-back, then transparent back-to-front
+            draw_phase(world, &mut tracked_pass, view, opaque);
+            draw_phase(world, &mut tracked_pass, view, alpha_mask);
+            draw_phase(world, &mut tracked_pass, view, transparent);
+        }
+        Ok(())
+    }
+}
+
+fn draw_phase<'w, I: PhaseItem>(
+    world: &'w World,
+    pass: &mut TrackedRenderPass<'w>,
+    view: Entity,
+    phase: &RenderPhase<I>,
+) {
+    let mut draw_functions = world.resource::<DrawFunctions<I>>().write();
+    for item in &phase.items {
+        let draw_function = draw_functions.get_mut(item.draw_function()).unwrap();
+        draw_function.draw(world, pass, view, item);
+    }
+}
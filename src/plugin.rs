@@ -1,24 +1,252 @@
 /// Heavily inspired from the [render_to_texture][1] example
 /// [1]: https://github.com/bevyengine/bevy/blob/main/examples/3d/render_to_texture.rs
 use super::first_pass::*;
-use crate::default_gizmo;
+use crate::{default_gizmo, AxisLabels, DragOrbit, Gizmo, GizmoBillboard, GizmoLabelBundle};
 use bevy::{
     core_pipeline::RenderTargetClearColors,
+    ecs::{
+        query::{FilterFetch, WorldQuery},
+        system::Command,
+    },
     prelude::*,
-    reflect::TypeUuid,
     render::{
         camera::{Camera, RenderTarget},
         render_resource::{
-            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+            Extent3d, FilterMode, SamplerDescriptor, TextureDescriptor, TextureDimension,
+            TextureFormat, TextureUsages,
         },
+        renderer::RenderDevice,
         view::RenderLayers,
     },
+    transform::TransformSystem,
+    ui::UiSystem,
+    window::WindowScaleFactorChanged,
 };
 
 /// Attach this component to an entity featuring a [Transform] which you want to track the rotation.
+///
+/// Unless it is bound to a specific gizmo with [DrivesGizmo], it drives every gizmo instance that
+/// has no bound [TrackedRotator].
 #[derive(Component)]
 pub struct TrackedRotator;
 
+/// Attach this component next to a [TrackedRotator] to make it drive a specific gizmo instance.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DrivesGizmo(pub GizmoInstance);
+
+/// Makes a gizmo instance track another entity from now on.
+///
+/// The entity previously bound to `instance` loses its [TrackedRotator] and [DrivesGizmo]
+/// components, while `target` receives them. A `target` already driving another instance is left
+/// untouched, with a warning: retarget that instance first.
+///
+/// Usage:
+/// ```rust,ignore
+/// commands.add(RetargetGizmo {
+///     instance: GizmoInstance(0),
+///     target: new_active_camera,
+/// });
+/// ```
+pub struct RetargetGizmo {
+    pub instance: GizmoInstance,
+    pub target: Entity,
+}
+
+impl Command for RetargetGizmo {
+    fn write(self, world: &mut World) {
+        match world.get_entity(self.target) {
+            Some(target) => match target.get::<DrivesGizmo>() {
+                Some(DrivesGizmo(other)) if *other != self.instance => {
+                    warn!(
+                        "Cannot retarget gizmo instance {:?}: entity {:?} drives instance {:?}",
+                        self.instance, self.target, other
+                    );
+                    return;
+                }
+                _ => {}
+            },
+            None => {
+                warn!(
+                    "Cannot retarget gizmo instance {:?}: entity {:?} does not exist",
+                    self.instance, self.target
+                );
+                return;
+            }
+        }
+
+        let previous: Vec<Entity> = world
+            .query::<(Entity, &DrivesGizmo)>()
+            .iter(world)
+            .filter(|(e, drives)| drives.0 == self.instance && *e != self.target)
+            .map(|(e, _)| e)
+            .collect();
+        for e in previous {
+            world
+                .entity_mut(e)
+                .remove_bundle::<(DrivesGizmo, TrackedRotator)>();
+        }
+
+        world
+            .entity_mut(self.target)
+            .insert(TrackedRotator)
+            .insert(DrivesGizmo(self.instance));
+    }
+}
+
+/// Replaces the gizmo displayed by an instance, keeping its camera, texture and canvas.
+///
+/// The entities spawned by the previous [Gizmo] are despawned, and `gizmo` becomes the
+/// [PluginOptions::gizmo] of the instance.
+///
+/// Usage:
+/// ```rust,ignore
+/// commands.add(SetGizmo {
+///     instance: GizmoInstance(0),
+///     gizmo: VIEW_CUBE_GIZMO.into(),
+/// });
+/// ```
+pub struct SetGizmo {
+    pub instance: GizmoInstance,
+    pub gizmo: Gizmo,
+}
+
+impl Command for SetGizmo {
+    fn write(self, world: &mut World) {
+        let mut found = false;
+        let mut options = world.query::<(&GizmoInstance, &mut PluginOptions)>();
+        for (instance, mut options) in options.iter_mut(world) {
+            if *instance == self.instance {
+                options.gizmo = self.gizmo.clone();
+                found = true;
+            }
+        }
+        if !found {
+            warn!(
+                "Cannot set the gizmo of unknown instance {:?}",
+                self.instance
+            );
+            return;
+        }
+
+        // Instances not spawned yet will use the new gizmo right away
+        let mesh_roots: Vec<Entity> = world
+            .query_filtered::<(Entity, &GizmoInstance), With<GizmoMeshRoot>>()
+            .iter(world)
+            .filter(|(_, instance)| **instance == self.instance)
+            .map(|(entity, _)| entity)
+            .collect();
+        for entity in mesh_roots {
+            let mut mesh_root = world.entity_mut(entity);
+            mesh_root.despawn_descendants();
+            mesh_root.insert(SwapGizmoParts);
+        }
+    }
+}
+
+/// Tags the entities spawned by the plugin itself: the UI camera, and for each gizmo instance, the
+/// entity holding its [PluginOptions], its first pass camera, the root of its meshes, its axis
+/// labels and its canvas. The parts spawned by a [Gizmo] are not tagged: they are children of its
+/// meshes root, and despawned with it.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct GizmoPluginEntity;
+
+/// Despawns a gizmo instance, including the entity holding its [PluginOptions].
+///
+/// Render targets cannot be unregistered from [RenderTargetClearColors] in this version of Bevy,
+/// and the clear pass panics on a registered image which was freed. The texture of the instance is
+/// thus recycled rather than freed: it is shrunk to 1x1 and kept, along with its clear color, until
+/// the next instance spawned reuses it. The plugin never holds more textures than the largest
+/// number of instances alive at once.
+pub struct DespawnGizmo(pub GizmoInstance);
+
+impl Command for DespawnGizmo {
+    fn write(self, world: &mut World) {
+        despawn_instance_parts(world, self.0);
+
+        let roots: Vec<Entity> = world
+            .query_filtered::<(Entity, &GizmoInstance), With<PluginOptions>>()
+            .iter(world)
+            .filter(|(_, instance)| **instance == self.0)
+            .map(|(entity, _)| entity)
+            .collect();
+        for root in roots {
+            if let Some(GizmoTexture(handle)) = world.entity_mut(root).remove::<GizmoTexture>() {
+                world.resource_scope(|world, mut retired: Mut<RetiredGizmoTextures>| {
+                    retired.retire(handle, &mut world.resource_mut::<Assets<Image>>());
+                });
+            }
+            world.entity_mut(root).despawn_recursive();
+        }
+    }
+}
+
+/// Despawns every gizmo instance as with [DespawnGizmo], as well as the UI camera spawned by the
+/// plugin, if any. Spawning a new instance afterwards spawns the UI camera again if needed.
+pub struct DespawnAllGizmos;
+
+impl Command for DespawnAllGizmos {
+    fn write(self, world: &mut World) {
+        let instances: Vec<GizmoInstance> = world
+            .query_filtered::<&GizmoInstance, With<PluginOptions>>()
+            .iter(world)
+            .copied()
+            .collect();
+        for instance in instances {
+            DespawnGizmo(instance).write(world);
+        }
+
+        let ui_cameras: Vec<Entity> = world
+            .query_filtered::<Entity, With<PluginUiCamera>>()
+            .iter(world)
+            .collect();
+        for ui_camera in ui_cameras {
+            world.entity_mut(ui_camera).despawn_recursive();
+        }
+    }
+}
+
+/// Despawns and spawns again a gizmo instance from its current [PluginOptions], keeping the entity
+/// holding them as well as its texture.
+pub struct RespawnGizmo(pub GizmoInstance);
+
+impl Command for RespawnGizmo {
+    fn write(self, world: &mut World) {
+        despawn_instance_parts(world, self.0);
+    }
+}
+
+/// Identifies a gizmo instance. The entity holding its [PluginOptions], its camera, UI node and
+/// the root of its meshes carry the instance they belong to; the parts spawned by its [Gizmo]
+/// under that root do not.
+///
+/// To add a gizmo at runtime, spawn an entity with a [GizmoInstance] and the [PluginOptions] it
+/// should use; ids must be unique.
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GizmoInstance(pub u32);
+
+impl GizmoInstance {
+    /// The render layer used by default by the first pass of this instance, see
+    /// [PluginOptions::render_layer].
+    pub fn render_layer(&self) -> Option<RenderLayers> {
+        // Layer 0 is the one used by default by every Bevy camera and mesh.
+        let layer = self.0.checked_add(1)?;
+        if layer < RenderLayers::TOTAL_LAYERS as u32 {
+            Some(RenderLayers::layer(layer as u8))
+        } else {
+            None
+        }
+    }
+}
+
+/// The texture a gizmo instance renders to, inserted next to its [GizmoInstance] and
+/// [PluginOptions] once it is spawned. Use it to display the gizmo elsewhere than in the Bevy UI
+/// canvas, e.g. with [PluginOptions::texture_only].
+///
+/// The texture is kept when the instance is respawned, and recycled by another instance once it is
+/// despawned (see [DespawnGizmo]).
+#[derive(Component, Clone, Debug)]
+pub struct GizmoTexture(pub Handle<Image>);
+
 /// Represents a position on the canvas. You can use one of the four presets, or specify your custom
 /// [Rect] (relative to the screen).
 #[derive(Clone)]
@@ -30,17 +258,188 @@ pub enum CanvasLocation {
     Custom(Rect<Val>),
 }
 
-/// A gizmo, as created with the `gizmo!` macro.
-pub type Gizmo =
-    fn(RenderLayers, &mut Commands, ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>);
+/// Which rotation of the tracked entity is displayed by the gizmo.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "ron-gizmo", derive(serde::Deserialize))]
+pub enum RotationTracking {
+    /// The rotation of its [Transform], i.e. relative to its parent.
+    Local,
+    /// Its world-space rotation, taking all of its ancestors into account. Use this when tracking
+    /// a camera nested in a rig.
+    Global,
+}
 
 /// Options that enable plugin behavior customization.
-/// Defaults to 64x64, [CanvasLocation::BottomLeft].
-#[derive(Clone)]
+/// Defaults to 64x64, [CanvasLocation::BottomLeft], [RotationTracking::Local], no drag orbit, no
+/// axis labels, visible, on the render layer of its [GizmoInstance], rendered to a texture displayed
+/// in the UI canvas, without supersampling.
+///
+/// Each gizmo instance reads its own options, stored as a component next to its [GizmoInstance].
+/// Changes to its size, location and visibility are applied to the displayed gizmo.
+#[derive(Component, Clone)]
 pub struct PluginOptions {
+    /// Size of the gizmo in logical pixels. Its texture is sized in physical pixels of the primary
+    /// window, and resized when the scale factor of the window changes.
     pub size: u32,
     pub location: CanvasLocation,
     pub gizmo: Gizmo,
+    pub tracking: RotationTracking,
+    pub drag_orbit: Option<DragOrbit>,
+    /// Labels added at the tip of the gizmo axes, on top of the [Gizmo] itself.
+    pub axis_labels: Option<AxisLabels>,
+    /// While `false`, the gizmo is neither displayed, rendered nor clickable.
+    pub visible: bool,
+    /// Render layer of the first pass, which must be used by nothing else; the one of
+    /// [GizmoInstance::render_layer] if `None`. Layer 0, used by default by every Bevy camera and
+    /// mesh, is rejected, as is a layer already used by another instance: the plugin panics for
+    /// the instances it is built with, and logs an error without spawning the ones added at
+    /// runtime. Read when the instance is spawned, see [RespawnGizmo].
+    pub render_layer: Option<u8>,
+    /// Only renders the gizmo to its [GizmoTexture], without displaying it in the UI canvas; the
+    /// `location` is then ignored. Read when the instance is spawned.
+    pub texture_only: bool,
+    /// Renders the gizmo to this target, e.g. an existing image or a secondary window, instead of
+    /// a [GizmoTexture] displayed in the UI canvas; `size` and `location` are then ignored. Images
+    /// are cleared to transparent, unless they already have a clear color in
+    /// [RenderTargetClearColors]; windows are cleared as usual. Read when the instance is spawned.
+    pub render_target: Option<RenderTarget>,
+    /// Draws the gizmo straight into a corner viewport of the target of the main 3D camera, at
+    /// `size` and `location`, instead of going through a texture displayed in the UI canvas. This
+    /// saves a texture and a pass per gizmo. Ignored if `render_target` is set. Read when the
+    /// instance is spawned.
+    pub overlay: bool,
+    /// Renders the texture this many times larger in each dimension, and lets it be filtered down
+    /// when displayed, to smooth the edges of thin parts. 1 disables it; 2 is the most effective,
+    /// as bilinear filtering averages 2x2 texels: with 3 or 4, texels are skipped and edges still
+    /// alias. Larger factors, or ones making the texture larger than the device supports, log an
+    /// error and fall back to 1. Ignored in overlay mode.
+    pub supersampling: u32,
+}
+
+/// Largest [PluginOptions::supersampling] factor.
+const MAX_SUPERSAMPLING: u32 = 4;
+
+impl PluginOptions {
+    /// The render layer used by the first pass of `instance`, if valid.
+    pub(crate) fn first_pass_layer(&self, instance: GizmoInstance) -> Option<RenderLayers> {
+        match self.render_layer {
+            Some(layer) if layer > 0 && (layer as usize) < RenderLayers::TOTAL_LAYERS => {
+                Some(RenderLayers::layer(layer))
+            }
+            Some(_) => None,
+            None => instance.render_layer(),
+        }
+    }
+
+    /// Size of the texture the gizmo is rendered to, in physical pixels of a window of
+    /// `scale_factor`, so that it stays sharp on HiDPI displays, times the supersampling factor.
+    /// Textures are at most `max_dimension` wide, see [RenderDevice::limits].
+    pub(crate) fn texture_size(&self, scale_factor: f64, max_dimension: u32) -> Extent3d {
+        let size = ((self.size as f64 * scale_factor).round() as u32).clamp(1, max_dimension);
+        let size = match self.supersampling {
+            factor @ 1..=MAX_SUPERSAMPLING if size * factor <= max_dimension => size * factor,
+            factor => {
+                error!(
+                    "Unsupported supersampling factor {} for a gizmo texture of {}px, using 1",
+                    factor, size
+                );
+                size
+            }
+        };
+        Extent3d {
+            width: size,
+            height: size,
+            ..default()
+        }
+    }
+
+    /// Whether the gizmo is drawn over the target of the main 3D camera, see [Self::overlay].
+    pub(crate) fn is_overlay(&self) -> bool {
+        self.overlay && self.render_target.is_none()
+    }
+
+    /// Whether the texture of the gizmo is displayed in the UI canvas, which needs a UI camera.
+    pub(crate) fn is_in_ui(&self) -> bool {
+        !self.texture_only && !self.is_overlay() && self.render_target.is_none()
+    }
+
+    /// Top left corner and size of the viewport of an overlay gizmo, in logical pixels, from its
+    /// location in a target of `target_size` logical pixels.
+    pub(crate) fn overlay_viewport(&self, target_size: Vec2) -> (Vec2, f32) {
+        let position = self.ui_style().position;
+        let size = self.size as f32;
+        let offset = |start: Val, end: Val, extent: f32| {
+            let resolve = |val: Val| match val {
+                Val::Px(px) => Some(px),
+                Val::Percent(percent) => Some(percent / 100.0 * extent),
+                _ => None,
+            };
+            resolve(start)
+                .or_else(|| resolve(end).map(|end| extent - size - end))
+                .unwrap_or(0.0)
+        };
+        let min = Vec2::new(
+            offset(position.left, position.right, target_size.x),
+            offset(position.top, position.bottom, target_size.y),
+        );
+        (min, size)
+    }
+
+    /// Top left corner and size of the viewport of an overlay gizmo, in physical pixels of a
+    /// target of `target_size` physical pixels and `scale_factor`. Viewports are moved inside
+    /// their target, or `None` if they do not fit in it.
+    pub(crate) fn fitted_overlay_viewport(
+        &self,
+        target_size: Vec2,
+        scale_factor: f32,
+    ) -> Option<(Vec2, f32)> {
+        let (min, size) = self.overlay_viewport(target_size / scale_factor);
+        let size = size * scale_factor;
+        if size > target_size.x || size > target_size.y {
+            return None;
+        }
+        Some((
+            (min * scale_factor).clamp(Vec2::ZERO, target_size - size),
+            size,
+        ))
+    }
+
+    /// Style of the UI node displaying the gizmo.
+    pub(crate) fn ui_style(&self) -> Style {
+        Style {
+            size: Size::new(Val::Px(self.size as f32), Val::Px(self.size as f32)),
+            display: if self.visible {
+                Display::Flex
+            } else {
+                Display::None
+            },
+            position_type: PositionType::Absolute,
+            position: match &self.location {
+                CanvasLocation::TopLeft => Rect {
+                    left: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    ..default()
+                },
+                CanvasLocation::TopRight => Rect {
+                    right: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    ..default()
+                },
+                CanvasLocation::BottomLeft => Rect {
+                    left: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    ..default()
+                },
+                CanvasLocation::BottomRight => Rect {
+                    right: Val::Px(0.0),
+                    bottom: Val::Px(0.0),
+                    ..default()
+                },
+                CanvasLocation::Custom(r) => *r,
+            },
+            ..default()
+        }
+    }
 }
 
 impl Default for PluginOptions {
@@ -48,15 +447,37 @@ impl Default for PluginOptions {
         Self {
             size: 64,
             location: CanvasLocation::BottomLeft,
-            gizmo: default_gizmo::GIZMO,
+            gizmo: default_gizmo::GIZMO.into(),
+            tracking: RotationTracking::Local,
+            drag_orbit: None,
+            axis_labels: None,
+            visible: true,
+            render_layer: None,
+            texture_only: false,
+            render_target: None,
+            overlay: false,
+            supersampling: 1,
         }
     }
 }
 
+/// How the plugin gets the UI camera displaying its canvas.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UiCameraSetup {
+    /// Reuses the UI camera of the app if there is one, and spawns one otherwise.
+    #[default]
+    Auto,
+    /// Never spawns a UI camera: the app provides its own.
+    Existing,
+    /// Always spawns a dedicated UI camera.
+    Spawn,
+}
+
 /// A plugin for displaying the reference frame orientation in the bottom left corner of the screen
 /// that updates when the TrackedRotator rotates.
 pub struct ViewportOrientationGizmoPlugin {
-    options: PluginOptions,
+    instances: Vec<PluginOptions>,
+    ui_camera: UiCameraSetup,
 }
 
 impl ViewportOrientationGizmoPlugin {
@@ -72,7 +493,22 @@ impl ViewportOrientationGizmoPlugin {
 
     /// Displays a customized gizmo.
     pub fn custom(options: PluginOptions) -> Self {
-        Self { options }
+        Self::multiple([options])
+    }
+
+    /// Displays one customized gizmo per element of `options`. The i-th gizmo is identified by
+    /// `GizmoInstance(i)`.
+    pub fn multiple(options: impl IntoIterator<Item = PluginOptions>) -> Self {
+        Self {
+            instances: options.into_iter().collect(),
+            ui_camera: default(),
+        }
+    }
+
+    /// Sets how the UI camera displaying the gizmos is obtained; [UiCameraSetup::Auto] by default.
+    pub fn with_ui_camera(mut self, ui_camera: UiCameraSetup) -> Self {
+        self.ui_camera = ui_camera;
+        self
     }
 }
 
@@ -84,13 +520,66 @@ impl Default for ViewportOrientationGizmoPlugin {
 
 impl Plugin for ViewportOrientationGizmoPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(self.options.clone())
-            .add_startup_system(setup)
-            .add_system(update_1st_pass_camera_transform);
+        let mut layers: Vec<(GizmoInstance, RenderLayers)> = Vec::new();
+        for (id, options) in self.instances.iter().enumerate() {
+            let instance = GizmoInstance(id as u32);
+            let layer = options.first_pass_layer(instance).unwrap_or_else(|| {
+                panic!(
+                    "Invalid render layer {:?} for gizmo instance {:?}",
+                    options.render_layer, instance
+                )
+            });
+            if let Some((other, _)) = layers.iter().find(|(_, l)| *l == layer) {
+                panic!(
+                    "Gizmo instances {:?} and {:?} use the same render layer",
+                    other, instance
+                );
+            }
+            layers.push((instance, layer));
+        }
+
+        for (id, options) in self.instances.iter().enumerate() {
+            app.world
+                .spawn()
+                .insert(GizmoInstance(id as u32))
+                .insert(options.clone())
+                .insert(GizmoPluginEntity);
+        }
+
+        app.init_resource::<RetiredGizmoTextures>()
+            .init_resource::<GizmoCursor>()
+            .insert_resource(self.ui_camera)
+            .add_startup_system(spawn_gizmo_instances)
+            .add_system_to_stage(CoreStage::PreUpdate, spawn_gizmo_instances)
+            // Runs after startup so that the UI camera of the app, if any, is already spawned
+            .add_system_to_stage(CoreStage::PreUpdate, spawn_ui_camera)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_gizmo_cursor.after(UiSystem::Focus),
+            )
+            .add_system(apply_options_changes)
+            .add_system(resize_gizmo_textures)
+            .add_system(swap_gizmo_parts)
+            // Runs right before propagation so that the gizmo reflects this frame's rotation
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                update_1st_pass_camera_transform.before(TransformSystem::TransformPropagate),
+            );
         init_app_rendering(app);
 
+        app.add_plugin(crate::overlay::OverlayPlugin)
+            .add_plugin(crate::label::LabelPlugin)
+            .add_plugin(crate::drag::DragPlugin)
+            .add_plugin(crate::scene_gizmo::SceneGizmoPlugin);
+
         #[cfg(feature = "click-reaction")]
         app.add_plugin(crate::click_reaction::ClickReactionPlugin);
+
+        #[cfg(feature = "ron-gizmo")]
+        app.add_plugin(crate::gizmo_description::GizmoDescriptionPlugin);
+
+        #[cfg(feature = "egui-widget")]
+        app.add_plugin(crate::egui_widget::EguiWidgetPlugin);
     }
 }
 
@@ -98,7 +587,8 @@ impl Plugin for ViewportOrientationGizmoPlugin {
 #[derive(Component, Default)]
 pub(crate) struct FirstPassCamera;
 
-/// Helper to quickly identify the first pass camera spawned in setup(...)
+/// Helper to quickly identify the first pass camera root of an instance. This is the entity
+/// holding the [GizmoInstance] and its [PluginOptions].
 #[derive(Component)]
 pub(crate) struct FirstPassCameraRoot;
 
@@ -106,122 +596,724 @@ pub(crate) struct FirstPassCameraRoot;
 #[derive(Component)]
 pub(crate) struct GizmoUi;
 
-/// This handle will point at the texture to which we will render in the first pass.
-const RENDER_IMAGE_HANDLE: HandleUntyped =
-    HandleUntyped::weak_from_u64(Image::TYPE_UUID, 13378939762009864042);
+/// Helper to quickly identify the entity the gizmo meshes are spawned under
+#[derive(Component)]
+pub(crate) struct GizmoMeshRoot;
+
+/// Marks the first pass cameras drawing their gizmo over the main 3D camera target, see
+/// [PluginOptions::overlay]
+#[derive(Component)]
+pub(crate) struct GizmoOverlay;
+
+/// Marks the first pass cameras of the hidden instances, which are neither rendered nor raycast
+#[derive(Component)]
+pub(crate) struct HiddenGizmo;
+
+/// Marks the instances which cannot be spawned because of their render layer, until respawned
+#[derive(Component)]
+struct RejectedGizmo;
+
+/// Marks the gizmo meshes roots whose parts must be spawned again, see [SetGizmo]
+#[derive(Component)]
+struct SwapGizmoParts;
+
+/// Despawns everything spawned for an instance but the entity holding its [PluginOptions], so
+/// that the instance is spawned again from its current options.
+fn despawn_instance_parts(world: &mut World, instance: GizmoInstance) {
+    let entities: Vec<(Entity, bool)> = world
+        .query_filtered::<(Entity, &GizmoInstance, Option<&FirstPassCameraRoot>), Or<(
+            With<FirstPassCameraRoot>,
+            With<GizmoMeshRoot>,
+            With<GizmoUi>,
+        )>>()
+        .iter(world)
+        .filter(|(_, i, _)| **i == instance)
+        .map(|(entity, _, root)| (entity, root.is_some()))
+        .collect();
+    let rejected: Vec<Entity> = world
+        .query_filtered::<(Entity, &GizmoInstance), With<RejectedGizmo>>()
+        .iter(world)
+        .filter(|(_, i)| **i == instance)
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in rejected {
+        world.entity_mut(entity).remove::<RejectedGizmo>();
+    }
+
+    for (entity, is_root) in entities {
+        let mut entity = world.entity_mut(entity);
+        if is_root {
+            entity.remove::<FirstPassCameraRoot>();
+            entity.despawn_descendants();
+        } else {
+            entity.despawn_recursive();
+        }
+    }
+}
+
+/// Textures of despawned instances, see [DespawnGizmo]
+#[derive(Default)]
+pub(crate) struct RetiredGizmoTextures(Vec<Handle<Image>>);
+
+impl RetiredGizmoTextures {
+    /// Shrinks the texture to save memory until it is reused.
+    fn retire(&mut self, handle: Handle<Image>, images: &mut Assets<Image>) {
+        if let Some(image) = images.get_mut(&handle) {
+            image.resize(Extent3d {
+                width: 1,
+                height: 1,
+                ..default()
+            });
+        }
+        self.0.push(handle);
+    }
+}
+
+/// Resource holding the gizmo under the mouse cursor, if any, updated in [CoreStage::PreUpdate].
+#[derive(Default)]
+pub struct GizmoCursor {
+    /// The hovered instance, with the cursor position in the target of its first pass camera
+    /// (origin at the bottom left, like window cursor positions).
+    pub(crate) hovered: Option<(GizmoInstance, Vec2)>,
+    /// Set by gizmos displayed outside of the Bevy UI canvas, e.g. in egui; used on the next
+    /// update when no gizmo of the canvas is hovered.
+    pub(crate) reported: Option<(GizmoInstance, Vec2)>,
+}
+
+impl GizmoCursor {
+    /// The gizmo under the mouse cursor, if any.
+    pub fn hovered_instance(&self) -> Option<GizmoInstance> {
+        self.hovered.map(|(instance, _)| instance)
+    }
+}
+
+/// Helper to quickly identify the UI camera spawned by the plugin
+#[derive(Component)]
+struct PluginUiCamera;
+
+/// Finds the [TrackedRotator] driving `instance`: the one bound to it through [DrivesGizmo], or
+/// else the first unbound one.
+pub(crate) fn find_tracked_rotator<'a>(
+    instance: GizmoInstance,
+    rotators: impl Iterator<Item = (Entity, Option<&'a DrivesGizmo>)>,
+) -> Option<Entity> {
+    let mut unbound = None;
+    for (entity, drives) in rotators {
+        match drives {
+            Some(drives) if drives.0 == instance => return Some(entity),
+            None if unbound.is_none() => unbound = Some(entity),
+            _ => {}
+        }
+    }
+    unbound
+}
+
+/// Computes the world-space rotation of `entity` from its own [Transform] and the ones of its
+/// ancestors, so that it is accurate even before transforms are propagated.
+pub(crate) fn global_rotation<F: WorldQuery>(
+    entity: Entity,
+    hierarchy: &Query<(&Transform, Option<&Parent>), F>,
+) -> Option<Quat>
+where
+    F::Fetch: FilterFetch,
+{
+    let (transform, parent) = hierarchy.get(entity).ok()?;
+    match parent {
+        Some(parent) => Some(global_rotation(parent.0, hierarchy)? * transform.rotation),
+        None => Some(transform.rotation),
+    }
+}
+
+/// The rotation of the space in which the gizmo displays the rotation of `entity`: the world-space
+/// rotation of its parent with [RotationTracking::Global], identity otherwise.
+pub(crate) fn tracking_space_rotation<F: WorldQuery>(
+    entity: Entity,
+    tracking: RotationTracking,
+    hierarchy: &Query<(&Transform, Option<&Parent>), F>,
+) -> Quat
+where
+    F::Fetch: FilterFetch,
+{
+    match (tracking, hierarchy.get(entity)) {
+        (RotationTracking::Global, Ok((_, Some(parent)))) => {
+            global_rotation(parent.0, hierarchy).unwrap_or_default()
+        }
+        _ => Quat::IDENTITY,
+    }
+}
+
+/// Walks up the hierarchy of `entity` until finding the [GizmoInstance] it belongs to.
+pub(crate) fn find_instance(
+    entity: Entity,
+    hierarchy: &Query<(Option<&Parent>, Option<&GizmoInstance>)>,
+) -> Option<GizmoInstance> {
+    let mut current = entity;
+    loop {
+        match hierarchy.get(current) {
+            Ok((_, Some(instance))) => return Some(*instance),
+            Ok((Some(parent), None)) => current = parent.0,
+            _ => return None,
+        }
+    }
+}
+
+/// Finds the gizmo under the cursor, and where the cursor is in its texture or window
+pub(crate) fn update_gizmo_cursor(
+    mut cursor: ResMut<GizmoCursor>,
+    windows: Res<Windows>,
+    gizmo_ui: Query<(&GizmoInstance, &Interaction, &GlobalTransform, &Node), With<GizmoUi>>,
+    instances: Query<(&GizmoInstance, &PluginOptions), With<FirstPassCameraRoot>>,
+    textures: Query<(&GizmoInstance, &GizmoTexture)>,
+    images: Res<Assets<Image>>,
+    overlays: Query<(&GizmoInstance, &Camera), With<GizmoOverlay>>,
+) {
+    let reported = cursor.reported.take();
+    let cursor_position = windows.get_primary().and_then(|w| w.cursor_position());
+
+    let over_ui = cursor_position.and_then(|cursor_position| {
+        gizmo_ui
+            .iter()
+            .find(|(_, interaction, _, _)| **interaction != Interaction::None)
+            .map(|(instance, _, global_transform, node)| {
+                // Get back cursor_position in the rendered texture coordinate system: the node is
+                // sized in logical pixels, the texture in physical ones
+                let min = global_transform.translation.truncate() - node.size / 2.0;
+                let texture_size = textures
+                    .iter()
+                    .find(|(i, _)| *i == instance)
+                    .and_then(|(_, texture)| images.get(&texture.0))
+                    .map_or(node.size, |image| image.size());
+                (
+                    *instance,
+                    (cursor_position - min) * texture_size / node.size,
+                )
+            })
+    });
+    // Gizmos rendered to a window fill it
+    let over_window = || {
+        instances.iter().find_map(|(instance, options)| {
+            match (&options.render_target, options.visible) {
+                (Some(RenderTarget::Window(id)), true) => windows
+                    .get(*id)
+                    .and_then(|w| w.cursor_position())
+                    .map(|position| (*instance, position)),
+                _ => None,
+            }
+        })
+    };
+    // Overlay gizmos are raycast as if their viewport covered their whole window
+    let over_overlay = || {
+        overlays.iter().find_map(|(instance, camera)| {
+            let (_, options) = instances.iter().find(|(i, _)| *i == instance)?;
+            let window = match &camera.target {
+                RenderTarget::Window(id) if options.visible => windows.get(*id)?,
+                _ => return None,
+            };
+            let scale_factor = window.scale_factor() as f32;
+            let position = window.cursor_position()? * scale_factor;
+            let physical_size = Vec2::new(
+                window.physical_width() as f32,
+                window.physical_height() as f32,
+            );
+            let (min, size) = options.fitted_overlay_viewport(physical_size, scale_factor)?;
+            // Viewports are positioned from the top left, cursors from the bottom left
+            let relative = (Vec2::new(position.x, physical_size.y - position.y) - min) / size;
+            if relative.cmplt(Vec2::ZERO).any() || relative.cmpgt(Vec2::ONE).any() {
+                return None;
+            }
+            Some((
+                *instance,
+                Vec2::new(relative.x, 1.0 - relative.y)
+                    * Vec2::new(window.width(), window.height()),
+            ))
+        })
+    };
+    cursor.hovered = over_ui
+        .or_else(over_window)
+        .or_else(over_overlay)
+        .or(reported);
+}
 
 /// Update the virtual camera transform
-fn update_1st_pass_camera_transform(
-    tracked_rotator: Query<&Transform, (With<TrackedRotator>, Without<FirstPassCameraRoot>)>,
-    mut first_pass_cam: Query<&mut Transform, With<FirstPassCameraRoot>>,
+pub(crate) fn update_1st_pass_camera_transform(
+    tracked_rotators: Query<(Entity, Option<&DrivesGizmo>), With<TrackedRotator>>,
+    hierarchy: Query<(&Transform, Option<&Parent>), Without<FirstPassCameraRoot>>,
+    mut first_pass_cams: Query<
+        (&GizmoInstance, &PluginOptions, &mut Transform),
+        With<FirstPassCameraRoot>,
+    >,
 ) {
-    if let Some(tracked_transform) = tracked_rotator.iter().next() {
-        let mut cam_transform = first_pass_cam.single_mut();
-        cam_transform.rotation = tracked_transform.rotation;
+    for (instance, plugin_options, mut cam_transform) in first_pass_cams.iter_mut() {
+        let tracked = match find_tracked_rotator(*instance, tracked_rotators.iter()) {
+            Some(tracked) => tracked,
+            None => continue,
+        };
+        let rotation = hierarchy.get(tracked).ok().map(|(transform, _)| {
+            tracking_space_rotation(tracked, plugin_options.tracking, &hierarchy)
+                * transform.rotation
+        });
+        if let Some(rotation) = rotation {
+            cam_transform.rotation = rotation;
+        }
+    }
+}
+
+/// Setup the UI camera displaying the plugin canvas, once there is something to display
+fn spawn_ui_camera(
+    mut commands: Commands,
+    setup: Res<UiCameraSetup>,
+    instances: Query<&PluginOptions>,
+    plugin_ui_cameras: Query<(), With<PluginUiCamera>>,
+    ui_cameras: Query<(), With<CameraUi>>,
+) {
+    let spawn = match *setup {
+        UiCameraSetup::Auto => ui_cameras.is_empty(),
+        UiCameraSetup::Existing => false,
+        UiCameraSetup::Spawn => plugin_ui_cameras.is_empty(),
+    };
+    if spawn && instances.iter().any(PluginOptions::is_in_ui) {
+        commands
+            .spawn_bundle(UiCameraBundle::default())
+            .insert(PluginUiCamera)
+            .insert(GizmoPluginEntity);
     }
 }
 
-/// Setup virtual camera, gizmo mesh and plugin canvas
-fn setup(
-    plugin_options: Res<PluginOptions>,
+/// Setup virtual camera, gizmo mesh and plugin canvas of every new instance
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn spawn_gizmo_instances(
+    instances: Query<
+        (
+            Entity,
+            &GizmoInstance,
+            &PluginOptions,
+            Option<&GizmoTexture>,
+        ),
+        (Without<FirstPassCameraRoot>, Without<RejectedGizmo>),
+    >,
+    first_pass_cams: Query<(&GizmoInstance, &RenderLayers), With<FirstPassCamera>>,
     mut commands: Commands,
-    meshes: ResMut<Assets<Mesh>>,
-    materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
     mut images: ResMut<Assets<Image>>,
+    mut retired_textures: ResMut<RetiredGizmoTextures>,
     mut clear_colors: ResMut<RenderTargetClearColors>,
+    windows: Res<Windows>,
+    render_device: Res<RenderDevice>,
 ) {
-    let size = Extent3d {
-        width: plugin_options.size,
-        height: plugin_options.size,
-        ..default()
-    };
+    let scale_factor = ui_scale_factor(&windows);
+    let max_dimension = render_device.limits().max_texture_dimension_2d;
+    let mut used_layers: Vec<(GizmoInstance, RenderLayers)> = first_pass_cams
+        .iter()
+        .map(|(instance, layer)| (*instance, *layer))
+        .collect();
+    for (entity, instance, plugin_options, texture) in instances.iter() {
+        // This specifies the layer used for the first pass, which will be attached to the first pass camera and coordinate system.
+        let first_pass_layer = match plugin_options.first_pass_layer(*instance) {
+            Some(layer) => layer,
+            None => {
+                error!(
+                    "Invalid render layer {:?} for gizmo instance {:?}, not spawning it",
+                    plugin_options.render_layer, instance
+                );
+                commands.entity(entity).insert(RejectedGizmo);
+                continue;
+            }
+        };
+        // Rejected once, rather than having two instances render each other's gizmo
+        if let Some((other, _)) = used_layers
+            .iter()
+            .find(|(i, layer)| i != instance && *layer == first_pass_layer)
+        {
+            error!(
+                "Gizmo instance {:?} uses the render layer of instance {:?}, not spawning it",
+                instance, other
+            );
+            commands.entity(entity).insert(RejectedGizmo);
+            continue;
+        }
+        used_layers.push((*instance, first_pass_layer));
 
-    // This is the texture that will be rendered to.
-    let mut image = Image {
-        texture_descriptor: TextureDescriptor {
-            label: None,
-            size,
-            dimension: TextureDimension::D2,
-            format: TextureFormat::Bgra8UnormSrgb,
-            mip_level_count: 1,
-            sample_count: 1,
-            usage: TextureUsages::TEXTURE_BINDING
-                | TextureUsages::COPY_DST
-                | TextureUsages::RENDER_ATTACHMENT,
-        },
-        ..default()
-    };
-    image.resize(size);
-
-    let image_handle = images.set(RENDER_IMAGE_HANDLE, image);
-
-    // This specifies the layer used for the first pass, which will be attached to the first pass camera and coordinate system.
-    let first_pass_layer = RenderLayers::layer(1);
-
-    // What will be rendered to the texture
-    (plugin_options.gizmo)(first_pass_layer, &mut commands, meshes, materials);
-
-    // First pass camera capturing what will be rendered to the texture
-    let render_target = RenderTarget::Image(image_handle);
-    clear_colors.insert(render_target.clone(), Color::rgba(0.0, 0.0, 0.0, 0.0));
-    commands
-        .spawn()
-        .insert(Transform::identity().looking_at(Vec3::new(0.0, 0.0, -3.0), Vec3::Y))
-        .insert(GlobalTransform::identity())
-        .insert(FirstPassCameraRoot)
-        .with_children(|parent| {
-            parent
-                .spawn_bundle(PerspectiveCameraBundle::<FirstPassCamera> {
+        // This is the texture that will be rendered to, unless rendering to a target of the app.
+        let texture = texture.map(|GizmoTexture(handle)| handle.clone());
+        let overlay = plugin_options.is_overlay();
+        let (image_handle, render_target) = if overlay || plugin_options.render_target.is_some() {
+            // Respawned without a texture: it is not needed anymore
+            if let Some(handle) = texture {
+                commands.entity(entity).remove::<GizmoTexture>();
+                retired_textures.retire(handle, &mut images);
+            }
+            // Overlay cameras follow the target of the main 3D camera
+            (
+                None,
+                plugin_options.render_target.clone().unwrap_or_default(),
+            )
+        } else {
+            let size = plugin_options.texture_size(scale_factor, max_dimension);
+            let handle = match texture.or_else(|| retired_textures.0.pop()) {
+                Some(handle) => {
+                    if let Some(image) = images.get_mut(&handle) {
+                        if image.texture_descriptor.size != size {
+                            image.resize(size);
+                        }
+                    }
+                    handle
+                }
+                None => {
+                    let mut image = Image {
+                        texture_descriptor: TextureDescriptor {
+                            label: None,
+                            size,
+                            dimension: TextureDimension::D2,
+                            format: TextureFormat::Bgra8UnormSrgb,
+                            mip_level_count: 1,
+                            sample_count: 1,
+                            usage: TextureUsages::TEXTURE_BINDING
+                                | TextureUsages::COPY_DST
+                                | TextureUsages::RENDER_ATTACHMENT,
+                        },
+                        // Downsamples supersampled textures
+                        sampler_descriptor: SamplerDescriptor {
+                            mag_filter: FilterMode::Linear,
+                            min_filter: FilterMode::Linear,
+                            ..default()
+                        },
+                        ..default()
+                    };
+                    image.resize(size);
+                    images.add(image)
+                }
+            };
+            commands.entity(entity).insert(GizmoTexture(handle.clone()));
+            (Some(handle.clone()), RenderTarget::Image(handle))
+        };
+
+        // What will be rendered to the texture
+        commands
+            .spawn_bundle(TransformBundle::identity())
+            .insert(*instance)
+            .insert(GizmoMeshRoot)
+            .insert(GizmoPluginEntity)
+            .insert(first_pass_layer)
+            .with_children(|parent| {
+                spawn_gizmo_parts(
+                    plugin_options,
+                    first_pass_layer,
+                    parent,
+                    &mut meshes,
+                    &mut materials,
+                )
+            });
+
+        // First pass camera capturing what will be rendered to the texture
+        // Only images: the clear pass panics on registered windows which are not extracted yet,
+        // and clears the extracted ones anyway
+        if let RenderTarget::Image(_) = render_target {
+            if image_handle.is_some() || clear_colors.get(&render_target).is_none() {
+                clear_colors.insert(render_target.clone(), Color::rgba(0.0, 0.0, 0.0, 0.0));
+            }
+        }
+        commands
+            .entity(entity)
+            .insert(Transform::identity().looking_at(Vec3::new(0.0, 0.0, -3.0), Vec3::Y))
+            .insert(GlobalTransform::identity())
+            .insert(FirstPassCameraRoot)
+            .insert(GizmoPluginEntity)
+            .with_children(|parent| {
+                let mut camera = parent.spawn_bundle(PerspectiveCameraBundle::<FirstPassCamera> {
                     camera: Camera {
                         target: render_target,
                         ..default()
                     },
                     transform: Transform::from_translation(Vec3::new(0.0, 0.0, 3.0)),
                     ..PerspectiveCameraBundle::new()
-                })
-                .insert(first_pass_layer);
-        });
+                });
+                camera
+                    .insert(first_pass_layer)
+                    .insert(*instance)
+                    .insert(GizmoPluginEntity);
+                if overlay {
+                    camera.insert(GizmoOverlay);
+                }
+                // Options changed before spawning are not seen by apply_options_changes
+                if !plugin_options.visible {
+                    camera.insert(HiddenGizmo);
+                }
+            });
 
-    // Create UI Camera
-    commands.spawn_bundle(UiCameraBundle::default());
-
-    // Display RenderTexture in bottom left corner of UI canvas
-    commands
-        .spawn_bundle(NodeBundle {
-            style: Style {
-                size: Size::new(
-                    Val::Px(plugin_options.size as f32),
-                    Val::Px(plugin_options.size as f32),
-                ),
-                position_type: PositionType::Absolute,
-                position: match plugin_options.location {
-                    CanvasLocation::TopLeft => Rect {
-                        left: Val::Px(0.0),
-                        top: Val::Px(0.0),
-                        ..default()
-                    },
-                    CanvasLocation::TopRight => Rect {
-                        right: Val::Px(0.0),
-                        top: Val::Px(0.0),
-                        ..default()
-                    },
-                    CanvasLocation::BottomLeft => Rect {
-                        left: Val::Px(0.0),
-                        bottom: Val::Px(0.0),
-                        ..default()
-                    },
-                    CanvasLocation::BottomRight => Rect {
-                        right: Val::Px(0.0),
-                        bottom: Val::Px(0.0),
-                        ..default()
-                    },
-                    CanvasLocation::Custom(r) => r,
-                },
+        let image_handle = match image_handle {
+            Some(handle) if !plugin_options.texture_only => handle,
+            _ => continue,
+        };
+
+        // Display RenderTexture at the requested location of the UI canvas
+        commands
+            .spawn_bundle(NodeBundle {
+                style: plugin_options.ui_style(),
+                image: UiImage::from(image_handle),
                 ..default()
-            },
-            image: UiImage::from(RENDER_IMAGE_HANDLE.typed()),
+            })
+            .insert(Interaction::default())
+            .insert(GizmoUi)
+            .insert(*instance)
+            .insert(GizmoPluginEntity);
+    }
+}
+
+/// Moves the canvas and shows or hides the instances whose options changed
+#[allow(clippy::type_complexity)]
+fn apply_options_changes(
+    mut commands: Commands,
+    instances: Query<
+        (&GizmoInstance, &PluginOptions),
+        (With<FirstPassCameraRoot>, Changed<PluginOptions>),
+    >,
+    mut gizmo_ui: Query<(&GizmoInstance, &mut Style), With<GizmoUi>>,
+    first_pass_cams: Query<(Entity, &GizmoInstance), With<FirstPassCamera>>,
+) {
+    for (instance, plugin_options) in instances.iter() {
+        for (_, mut style) in gizmo_ui.iter_mut().filter(|(i, _)| *i == instance) {
+            *style = plugin_options.ui_style();
+        }
+
+        for (camera, _) in first_pass_cams.iter().filter(|(_, i)| *i == instance) {
+            if plugin_options.visible {
+                commands.entity(camera).remove::<HiddenGizmo>();
+            } else {
+                commands.entity(camera).insert(HiddenGizmo);
+            }
+        }
+    }
+}
+
+/// The scale factor of the window displaying the UI canvas
+pub(crate) fn ui_scale_factor(windows: &Windows) -> f64 {
+    windows
+        .get_primary()
+        .map_or(1.0, |window| window.scale_factor())
+}
+
+/// Resizes the textures of the instances whose options changed, or of all of them when the scale
+/// factor of a window changed
+fn resize_gizmo_textures(
+    mut scale_factor_changes: EventReader<WindowScaleFactorChanged>,
+    windows: Res<Windows>,
+    mut images: ResMut<Assets<Image>>,
+    render_device: Res<RenderDevice>,
+    instances: Query<(&PluginOptions, &GizmoTexture, ChangeTrackers<PluginOptions>)>,
+) {
+    let rescaled = scale_factor_changes.iter().count() > 0;
+    let scale_factor = ui_scale_factor(&windows);
+    let max_dimension = render_device.limits().max_texture_dimension_2d;
+    for (plugin_options, texture, changes) in instances.iter() {
+        if !rescaled && !changes.is_changed() {
+            continue;
+        }
+        let size = plugin_options.texture_size(scale_factor, max_dimension);
+        if let Some(image) = images.get_mut(&texture.0) {
+            if image.texture_descriptor.size != size {
+                image.resize(size);
+            }
+        }
+    }
+}
+
+/// Spawns the parts of the gizmo of an instance, including its axis labels
+fn spawn_gizmo_parts(
+    plugin_options: &PluginOptions,
+    layer: RenderLayers,
+    parent: &mut ChildBuilder,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    plugin_options.gizmo.spawn(layer, parent, meshes, materials);
+    for (label, position) in plugin_options.axis_labels.iter().flat_map(|l| l.labels()) {
+        parent
+            .spawn_bundle(GizmoLabelBundle {
+                label,
+                transform: Transform::from_translation(position),
+                ..default()
+            })
+            .insert(GizmoBillboard)
+            .insert(layer)
+            .insert(GizmoPluginEntity);
+    }
+}
+
+/// Spawns the parts of the gizmos replaced with [SetGizmo]
+fn swap_gizmo_parts(
+    mut commands: Commands,
+    mesh_roots: Query<(Entity, &GizmoInstance, &RenderLayers), With<SwapGizmoParts>>,
+    instances: Query<(&GizmoInstance, &PluginOptions), With<FirstPassCameraRoot>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, instance, layer) in mesh_roots.iter() {
+        if let Some((_, plugin_options)) = instances.iter().find(|(i, _)| *i == instance) {
+            commands.entity(entity).with_children(|parent| {
+                spawn_gizmo_parts(plugin_options, *layer, parent, &mut meshes, &mut materials)
+            });
+        }
+        commands.entity(entity).remove::<SwapGizmoParts>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(location: CanvasLocation) -> PluginOptions {
+        PluginOptions {
+            size: 100,
+            location,
+            overlay: true,
             ..default()
-        })
-        .insert(Interaction::default())
-        .insert(GizmoUi);
+        }
+    }
+
+    #[test]
+    fn overlay_viewport_resolves_presets() {
+        let target = Vec2::new(800.0, 600.0);
+        let viewport = |location| options(location).overlay_viewport(target);
+        assert_eq!(viewport(CanvasLocation::TopLeft), (Vec2::ZERO, 100.0));
+        assert_eq!(
+            viewport(CanvasLocation::TopRight),
+            (Vec2::new(700.0, 0.0), 100.0)
+        );
+        assert_eq!(
+            viewport(CanvasLocation::BottomLeft),
+            (Vec2::new(0.0, 500.0), 100.0)
+        );
+        assert_eq!(
+            viewport(CanvasLocation::BottomRight),
+            (Vec2::new(700.0, 500.0), 100.0)
+        );
+    }
+
+    #[test]
+    fn overlay_viewport_resolves_px_and_percent() {
+        let target = Vec2::new(800.0, 600.0);
+        let viewport = |rect| options(CanvasLocation::Custom(rect)).overlay_viewport(target);
+        assert_eq!(
+            viewport(Rect {
+                left: Val::Px(10.0),
+                top: Val::Percent(50.0),
+                ..default()
+            }),
+            (Vec2::new(10.0, 300.0), 100.0)
+        );
+        assert_eq!(
+            viewport(Rect {
+                right: Val::Percent(25.0),
+                bottom: Val::Px(20.0),
+                ..default()
+            }),
+            (Vec2::new(500.0, 480.0), 100.0)
+        );
+        // Left and top win over right and bottom, unset sides stick to the top left
+        assert_eq!(
+            viewport(Rect {
+                left: Val::Px(10.0),
+                right: Val::Px(30.0),
+                ..default()
+            }),
+            (Vec2::new(10.0, 0.0), 100.0)
+        );
+    }
+
+    #[test]
+    fn retarget_gizmo_moves_the_binding() {
+        let mut world = World::new();
+        let first = world
+            .spawn()
+            .insert(TrackedRotator)
+            .insert(DrivesGizmo(GizmoInstance(0)))
+            .id();
+        let second = world.spawn().id();
+        RetargetGizmo {
+            instance: GizmoInstance(0),
+            target: second,
+        }
+        .write(&mut world);
+
+        assert!(world.get::<TrackedRotator>(first).is_none());
+        assert!(world.get::<DrivesGizmo>(first).is_none());
+        assert!(world.get::<TrackedRotator>(second).is_some());
+        assert_eq!(
+            world.get::<DrivesGizmo>(second),
+            Some(&DrivesGizmo(GizmoInstance(0)))
+        );
+    }
+
+    #[test]
+    fn retarget_gizmo_keeps_the_bindings_of_other_instances() {
+        let mut world = World::new();
+        let first = world
+            .spawn()
+            .insert(TrackedRotator)
+            .insert(DrivesGizmo(GizmoInstance(0)))
+            .id();
+        let second = world
+            .spawn()
+            .insert(TrackedRotator)
+            .insert(DrivesGizmo(GizmoInstance(1)))
+            .id();
+        RetargetGizmo {
+            instance: GizmoInstance(0),
+            target: second,
+        }
+        .write(&mut world);
+
+        assert_eq!(
+            world.get::<DrivesGizmo>(first),
+            Some(&DrivesGizmo(GizmoInstance(0)))
+        );
+        assert_eq!(
+            world.get::<DrivesGizmo>(second),
+            Some(&DrivesGizmo(GizmoInstance(1)))
+        );
+    }
+
+    #[test]
+    fn texture_size_falls_back_to_no_supersampling() {
+        let size = |supersampling, max_dimension| {
+            PluginOptions {
+                size: 100,
+                supersampling,
+                ..default()
+            }
+            .texture_size(1.5, max_dimension)
+            .width
+        };
+        assert_eq!(size(1, 8192), 150);
+        assert_eq!(size(2, 8192), 300);
+        assert_eq!(size(4, 8192), 600);
+        assert_eq!(size(0, 8192), 150);
+        assert_eq!(size(5, 8192), 150);
+        assert_eq!(size(u32::MAX, 8192), 150);
+        assert_eq!(size(2, 200), 150);
+        assert_eq!(size(1, 100), 100);
+    }
+
+    #[test]
+    fn fitted_overlay_viewport_stays_in_target() {
+        let rect = Rect {
+            left: Val::Px(750.0),
+            top: Val::Px(-10.0),
+            ..default()
+        };
+        let options = options(CanvasLocation::Custom(rect));
+        assert_eq!(
+            options.fitted_overlay_viewport(Vec2::new(800.0, 600.0), 1.0),
+            Some((Vec2::new(700.0, 0.0), 100.0))
+        );
+        assert_eq!(
+            options.fitted_overlay_viewport(Vec2::new(1600.0, 1200.0), 2.0),
+            Some((Vec2::new(1400.0, 0.0), 200.0))
+        );
+        assert_eq!(
+            options.fitted_overlay_viewport(Vec2::new(800.0, 150.0), 2.0),
+            None
+        );
+    }
 }
@@ -0,0 +1,323 @@
+//! Gizmos authored in external tools, loaded as [Scene] assets (e.g. from glTF files).
+
+use bevy::{
+    asset::LoadState,
+    ecs::{entity::Entities, system::Command},
+    prelude::*,
+    render::view::RenderLayers,
+    scene::{InstanceId, SceneSpawner},
+};
+
+use crate::GizmoSpawner;
+
+/// A gizmo spawned from a [Scene] asset, such as a glTF scene. Every entity of the scene is placed
+/// on the gizmo render layer once the scene is spawned.
+///
+/// With the `click-reaction` feature, the meshes of the scene become clickable when they, or one
+/// of their ancestors in the scene, are either:
+/// * named after an axis: "X", "Y", "Z", "-X", "-Y" or "-Z" (Blender's ".001" like suffixes are
+///   ignored);
+/// * given a `gizmo_axis` custom property (glTF extras), holding one of the names above or a
+///   non-zero direction as an array of 3 numbers.
+///
+/// Usage:
+/// ```rust,ignore
+/// PluginOptions {
+///     gizmo: SceneGizmo::load("gizmos/arrows.glb#Scene0").into(),
+///     ..default()
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SceneGizmo {
+    source: SceneSource,
+}
+
+#[derive(Clone)]
+enum SceneSource {
+    Handle(Handle<Scene>),
+    Path(String),
+}
+
+impl SceneGizmo {
+    /// A gizmo spawning an already loaded or loading scene.
+    pub fn new(scene: Handle<Scene>) -> Self {
+        Self {
+            source: SceneSource::Handle(scene),
+        }
+    }
+
+    /// A gizmo spawning the scene at `path`, loaded through the [AssetServer] when the gizmo is
+    /// spawned.
+    pub fn load(path: impl Into<String>) -> Self {
+        Self {
+            source: SceneSource::Path(path.into()),
+        }
+    }
+}
+
+impl GizmoSpawner for SceneGizmo {
+    fn spawn(
+        &self,
+        layers: RenderLayers,
+        commands: &mut ChildBuilder,
+        _meshes: &mut Assets<Mesh>,
+        _materials: &mut Assets<StandardMaterial>,
+    ) {
+        commands
+            .spawn_bundle(TransformBundle::identity())
+            .insert(GizmoScene {
+                source: self.source.clone(),
+                layers,
+                handle: None,
+            });
+    }
+}
+
+pub(crate) struct SceneGizmoPlugin;
+
+impl Plugin for SceneGizmoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingGizmoScenes>()
+            .add_system(spawn_gizmo_scenes)
+            .add_system(setup_gizmo_scenes.after(spawn_gizmo_scenes));
+
+        #[cfg(feature = "click-reaction")]
+        app.add_system(setup_clickable_scene_meshes);
+    }
+}
+
+/// Entity under which a [SceneGizmo] is spawned, until its scene is loaded.
+#[derive(Component)]
+struct GizmoScene {
+    source: SceneSource,
+    layers: RenderLayers,
+    handle: Option<Handle<Scene>>,
+}
+
+/// Scenes queued for spawning, with the [GizmoScene] placeholder they belong to.
+#[derive(Default)]
+struct PendingGizmoScenes(Vec<(Entity, InstanceId, RenderLayers)>);
+
+/// Spawns the scenes once loaded. They are not spawned as children of their placeholder, which
+/// could be despawned before they are ready, see [AttachGizmoScene]. Placeholders whose scene
+/// failed to load are left empty.
+fn spawn_gizmo_scenes(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    scenes: Res<Assets<Scene>>,
+    mut scene_spawner: ResMut<SceneSpawner>,
+    mut pending: ResMut<PendingGizmoScenes>,
+    mut placeholders: Query<(Entity, &mut GizmoScene)>,
+) {
+    for (entity, mut scene) in placeholders.iter_mut() {
+        let handle = match &scene.source {
+            SceneSource::Handle(handle) => handle.clone(),
+            SceneSource::Path(path) => scene
+                .handle
+                .clone()
+                .unwrap_or_else(|| asset_server.load(path.as_str())),
+        };
+        if asset_server.get_load_state(&handle) == LoadState::Failed {
+            error!("Failed to load the scene of gizmo part {:?}", entity);
+            commands.entity(entity).remove::<GizmoScene>();
+            continue;
+        }
+        if scenes.get(&handle).is_none() {
+            // Keep the handle to keep loading it
+            scene.handle = Some(handle);
+            continue;
+        }
+        let instance = scene_spawner.spawn(handle);
+        pending.0.push((entity, instance, scene.layers));
+        commands.entity(entity).remove::<GizmoScene>();
+    }
+}
+
+/// Marks the meshes of the spawned [SceneGizmo]s.
+#[derive(Component)]
+struct GizmoSceneMesh;
+
+/// Attaches the spawned scenes to their placeholder. Scenes whose placeholder was despawned are
+/// forgotten if not spawned yet: Bevy cannot cancel queued scenes.
+fn setup_gizmo_scenes(
+    mut commands: Commands,
+    entities: &Entities,
+    scene_spawner: Res<SceneSpawner>,
+    mut pending: ResMut<PendingGizmoScenes>,
+) {
+    pending.0.retain(|(placeholder, instance, layers)| {
+        match scene_spawner.iter_instance_entities(*instance) {
+            Some(entities) => {
+                commands.add(AttachGizmoScene {
+                    placeholder: *placeholder,
+                    layers: *layers,
+                    entities: entities.collect(),
+                });
+                false
+            }
+            // Wait for the scene to be spawned
+            None => entities.contains(*placeholder),
+        }
+    });
+}
+
+/// Moves a spawned scene under its placeholder, on the gizmo render layer, or despawns it if the
+/// placeholder was despawned meanwhile.
+struct AttachGizmoScene {
+    placeholder: Entity,
+    layers: RenderLayers,
+    entities: Vec<Entity>,
+}
+
+impl Command for AttachGizmoScene {
+    fn write(self, world: &mut World) {
+        let roots: Vec<Entity> = self
+            .entities
+            .iter()
+            .copied()
+            .filter(|entity| world.get_entity(*entity).is_some())
+            .filter(|entity| world.get::<Parent>(*entity).is_none())
+            .collect();
+
+        if world.get_entity(self.placeholder).is_none() {
+            for root in roots {
+                world.entity_mut(root).despawn_recursive();
+            }
+            return;
+        }
+
+        for entity in self.entities {
+            if let Some(mut entity) = world.get_entity_mut(entity) {
+                entity.insert(self.layers);
+                if entity.contains::<Handle<Mesh>>() {
+                    entity.insert(GizmoSceneMesh);
+                }
+            }
+        }
+        world.entity_mut(self.placeholder).push_children(&roots);
+    }
+}
+
+#[cfg(feature = "click-reaction")]
+type AxisSources<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static Name>,
+        Option<&'static bevy::gltf::GltfExtras>,
+        Option<&'static Parent>,
+    ),
+>;
+
+/// Makes the meshes of the spawned [SceneGizmo]s clickable, when an axis is found for them.
+#[cfg(feature = "click-reaction")]
+fn setup_clickable_scene_meshes(
+    mut commands: Commands,
+    meshes: Query<Entity, Added<GizmoSceneMesh>>,
+    axis_sources: AxisSources,
+) {
+    for entity in meshes.iter() {
+        if let Some(axis) = find_axis(entity, &axis_sources) {
+            commands
+                .entity(entity)
+                .insert(crate::RaycastableGizmo::default())
+                .insert(axis);
+        }
+    }
+}
+
+/// Walks up the hierarchy of `entity`, looking for an axis in the names and extras.
+#[cfg(feature = "click-reaction")]
+fn find_axis(entity: Entity, axis_sources: &AxisSources) -> Option<crate::GizmoClickableAxis> {
+    let mut current = entity;
+    loop {
+        let (name, extras, parent) = axis_sources.get(current).ok()?;
+        let from_extras = extras
+            .and_then(|extras| serde_json::from_str::<serde_json::Value>(&extras.value).ok())
+            .and_then(|extras| axis_from_json(extras.get("gizmo_axis")?));
+        let from_name = name.and_then(|name| axis_from_name(name.as_str()));
+        if let Some(axis) = from_extras.or(from_name) {
+            return Some(axis);
+        }
+        current = parent?.0;
+    }
+}
+
+#[cfg(feature = "click-reaction")]
+fn axis_from_name(name: &str) -> Option<crate::GizmoClickableAxis> {
+    // Blender suffixes duplicated names with ".001", ".002"...
+    crate::GizmoClickableAxis::from_name(name.split('.').next().unwrap_or(""))
+}
+
+#[cfg(feature = "click-reaction")]
+fn axis_from_json(value: &serde_json::Value) -> Option<crate::GizmoClickableAxis> {
+    match value {
+        serde_json::Value::String(name) => crate::GizmoClickableAxis::from_name(name),
+        serde_json::Value::Array(direction) => {
+            let direction: Vec<f32> = direction
+                .iter()
+                .map(|c| c.as_f64().map(|c| c as f32))
+                .collect::<Option<_>>()?;
+            match direction[..] {
+                // A direction must be normalizable to be snapped to
+                [x, y, z] => Vec3::new(x, y, z)
+                    .try_normalize()
+                    .map(|_| crate::GizmoClickableAxis::Direction(Vec3::new(x, y, z))),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setup_gizmo_scenes_forgets_scenes_of_despawned_placeholders() {
+        let mut world = World::new();
+        let mut scene_spawner = SceneSpawner::default();
+        let kept = world.spawn().id();
+        let despawned = world.spawn().id();
+        world.despawn(despawned);
+        let layers = RenderLayers::layer(1);
+        world.insert_resource(PendingGizmoScenes(vec![
+            (kept, scene_spawner.spawn(Handle::default()), layers),
+            (despawned, scene_spawner.spawn(Handle::default()), layers),
+        ]));
+        world.insert_resource(scene_spawner);
+
+        let mut stage = SystemStage::single(setup_gizmo_scenes);
+        stage.run(&mut world);
+
+        let pending = world.resource::<PendingGizmoScenes>();
+        assert_eq!(pending.0.len(), 1);
+        assert_eq!(pending.0[0].0, kept);
+    }
+
+    #[cfg(feature = "click-reaction")]
+    #[test]
+    fn axis_from_name_ignores_blender_suffixes() {
+        assert_eq!(axis_from_name("X").map(Vec3::from), Some(Vec3::X));
+        assert_eq!(axis_from_name("-Y.001").map(Vec3::from), Some(-Vec3::Y));
+        assert_eq!(axis_from_name("z.012").map(Vec3::from), Some(Vec3::Z));
+        assert!(axis_from_name("Cube.001").is_none());
+        assert!(axis_from_name(".001").is_none());
+    }
+
+    #[cfg(feature = "click-reaction")]
+    #[test]
+    fn axis_from_json_reads_names_and_directions() {
+        let axis =
+            |json: &str| axis_from_json(&serde_json::from_str(json).unwrap()).map(Vec3::from);
+        assert_eq!(axis(r#""-X""#), Some(-Vec3::X));
+        assert_eq!(axis("[1, 1, 0]"), Some(Vec3::new(1.0, 1.0, 0.0)));
+        assert_eq!(axis("[0.5, -2, 3]"), Some(Vec3::new(0.5, -2.0, 3.0)));
+        assert!(axis(r#""W""#).is_none());
+        assert!(axis("[0, 0, 0]").is_none());
+        assert!(axis("[1, 1]").is_none());
+        assert!(axis(r#"[1, "1", 1]"#).is_none());
+        assert!(axis("1").is_none());
+    }
+}
@@ -0,0 +1,200 @@
+//! Optional camera controller aligning the tracked rotator with the clicked gizmo axis.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    find_tracked_rotator, tracking_space_rotation, ClickEvent, DrivesGizmo, FirstPassCameraRoot,
+    GizmoInstance, PluginOptions, RotationTracking, TrackedRotator,
+};
+
+/// Easing curves available to animate the view snapping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapEasing {
+    Linear,
+    QuadraticInOut,
+    CubicOut,
+    ExponentialOut,
+}
+
+impl SnapEasing {
+    /// Maps the animation progress `t` (in `[0, 1]`) to the interpolation factor.
+    pub fn sample(&self, t: f32) -> f32 {
+        match self {
+            SnapEasing::Linear => t,
+            SnapEasing::QuadraticInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            SnapEasing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            SnapEasing::ExponentialOut => {
+                if t >= 1.0 {
+                    1.0
+                } else {
+                    1.0 - 2f32.powf(-10.0 * t)
+                }
+            }
+        }
+    }
+}
+
+/// Options of the [SnapViewToAxisPlugin]. Can be modified at runtime through the resource of the
+/// same type.
+/// Defaults to a one second [SnapEasing::ExponentialOut] animation.
+#[derive(Clone)]
+pub struct SnapViewOptions {
+    pub duration: Duration,
+    pub easing: SnapEasing,
+}
+
+impl Default for SnapViewOptions {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_millis(1000),
+            easing: SnapEasing::ExponentialOut,
+        }
+    }
+}
+
+/// A plugin that, whenever a [GizmoClickableAxis][crate::GizmoClickableAxis] is left-clicked, rotates
+/// the [TrackedRotator] driving the clicked gizmo through the shortest path so that its Z points
+/// along the clicked axis, i.e. the view looks at the origin from that axis.
+///
+/// Clicks received while an animation is in flight are ignored.
+pub struct SnapViewToAxisPlugin {
+    options: SnapViewOptions,
+}
+
+impl SnapViewToAxisPlugin {
+    /// Snaps the view in one second with an exponential ease out.
+    pub fn new() -> Self {
+        Self::custom(default())
+    }
+
+    /// Snaps the view with customized animation options.
+    pub fn custom(options: SnapViewOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Default for SnapViewToAxisPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Plugin for SnapViewToAxisPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.options.clone())
+            .add_system(snap_on_click)
+            .add_system(animate_snap_view);
+    }
+}
+
+/// Present on a [TrackedRotator] while it is being snapped to an axis. Query for it if you need to
+/// pause your own camera controller during the animation.
+#[derive(Component)]
+pub struct SnapViewAnimation {
+    from: Quat,
+    to: Quat,
+    elapsed: Duration,
+    duration: Duration,
+    easing: SnapEasing,
+}
+
+fn snap_on_click(
+    mut commands: Commands,
+    mut events: EventReader<ClickEvent>,
+    options: Res<SnapViewOptions>,
+    rotators: Query<(Entity, Option<&DrivesGizmo>), With<TrackedRotator>>,
+    animations: Query<(), With<SnapViewAnimation>>,
+    gizmos: Query<(&GizmoInstance, &PluginOptions), With<FirstPassCameraRoot>>,
+    transforms: Query<(&Transform, Option<&Parent>)>,
+) {
+    for event in events.iter() {
+        if event.button != MouseButton::Left {
+            continue;
+        }
+        // Directions which cannot be normalized, e.g. zero, do not define a view to snap to
+        let axis = match event.axis.and_then(|axis| Vec3::from(axis).try_normalize()) {
+            Some(axis) => axis,
+            None => continue,
+        };
+        let instance = event.instance;
+        let rotator = match find_tracked_rotator(instance, rotators.iter()) {
+            Some(rotator) => rotator,
+            None => continue,
+        };
+        // Wait for animations to finish before allowing another rotation
+        if animations.contains(rotator) {
+            continue;
+        }
+        let transform = match transforms.get(rotator) {
+            Ok((transform, _)) => transform,
+            Err(_) => continue,
+        };
+
+        // Rotate in the space the gizmo displays, then bring the result back in local space
+        let tracking = gizmos
+            .iter()
+            .find(|(i, _)| **i == instance)
+            .map_or(RotationTracking::Local, |(_, o)| o.tracking);
+        let parent_rotation = tracking_space_rotation(rotator, tracking, &transforms);
+        let current = parent_rotation * transform.rotation;
+        // Shortest arc bringing Z onto the clicked axis; also handles (anti)parallel vectors
+        let target = Quat::from_rotation_arc(current * Vec3::Z, axis) * current;
+
+        commands.entity(rotator).insert(SnapViewAnimation {
+            from: transform.rotation,
+            to: (parent_rotation.inverse() * target).normalize(),
+            elapsed: Duration::ZERO,
+            duration: options.duration,
+            easing: options.easing,
+        });
+    }
+}
+
+fn animate_snap_view(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Transform, &mut SnapViewAnimation)>,
+) {
+    for (entity, mut transform, mut animation) in query.iter_mut() {
+        animation.elapsed += time.delta();
+        let t = if animation.elapsed >= animation.duration {
+            1.0
+        } else {
+            animation.elapsed.as_secs_f32() / animation.duration.as_secs_f32()
+        };
+
+        transform.rotation = animation
+            .from
+            .slerp(animation.to, animation.easing.sample(t));
+
+        if t >= 1.0 {
+            commands.entity(entity).remove::<SnapViewAnimation>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easings_start_at_0_and_end_at_1() {
+        for easing in [
+            SnapEasing::Linear,
+            SnapEasing::QuadraticInOut,
+            SnapEasing::CubicOut,
+            SnapEasing::ExponentialOut,
+        ] {
+            assert!(easing.sample(0.0).abs() < 1e-6, "{:?} at 0", easing);
+            assert!((easing.sample(1.0) - 1.0).abs() < 1e-6, "{:?} at 1", easing);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use bevy::{math::const_vec3, prelude::*, render::view::RenderLayers};
+
+use crate::click_reaction::*;
+use crate::{GizmoLabel, GizmoLabelBundle};
+
+/// Half the size of the cube.
+const HALF_SIZE: f32 = 0.5;
+/// Half the size of the central area of a face; the rest is shared by edges and corners.
+const FACE_HALF_SIZE: f32 = 0.3;
+
+/// Face labels, with the direction their text is read upward.
+const FACES: [(&str, Vec3, Vec3); 6] = [
+    ("RIGHT", Vec3::X, Vec3::Y),
+    ("LEFT", const_vec3!([-1.0, 0.0, 0.0]), Vec3::Y),
+    ("TOP", Vec3::Y, const_vec3!([0.0, 0.0, -1.0])),
+    ("BOTTOM", const_vec3!([0.0, -1.0, 0.0]), Vec3::Z),
+    ("FRONT", Vec3::Z, Vec3::Y),
+    ("BACK", const_vec3!([0.0, 0.0, -1.0]), Vec3::Y),
+];
+
+/// Extent of a part along one axis, given the part direction on that axis.
+fn part_extent(d: i32) -> (f32, f32) {
+    match d {
+        -1 => (-HALF_SIZE, -FACE_HALF_SIZE),
+        0 => (-FACE_HALF_SIZE, FACE_HALF_SIZE),
+        _ => (FACE_HALF_SIZE, HALF_SIZE),
+    }
+}
+
+fn view_cube(
+    layers: RenderLayers,
+    commands: &mut ChildBuilder,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    // Faces, edges and corners are respectively made of 1, 2 and 3 non-zero coordinates
+    let part_materials = [
+        Color::hex("d8d8d8").unwrap(),
+        Color::hex("b0b0b0").unwrap(),
+        Color::hex("909090").unwrap(),
+    ]
+    .map(|color| {
+        materials.add(StandardMaterial {
+            base_color: color,
+            unlit: true,
+            ..default()
+        })
+    });
+
+    for x in -1..=1 {
+        for y in -1..=1 {
+            for z in -1..=1 {
+                let direction = IVec3::new(x, y, z);
+                let axis = match (x, y, z) {
+                    (0, 0, 0) => continue,
+                    (1, 0, 0) => GizmoClickableAxis::X,
+                    (0, 1, 0) => GizmoClickableAxis::Y,
+                    (0, 0, 1) => GizmoClickableAxis::Z,
+                    (-1, 0, 0) => GizmoClickableAxis::XNeg,
+                    (0, -1, 0) => GizmoClickableAxis::YNeg,
+                    (0, 0, -1) => GizmoClickableAxis::ZNeg,
+                    _ => GizmoClickableAxis::Direction(direction.as_vec3()),
+                };
+                let ((min_x, max_x), (min_y, max_y), (min_z, max_z)) =
+                    (part_extent(x), part_extent(y), part_extent(z));
+                let non_zero = direction.abs().dot(IVec3::ONE) as usize;
+
+                commands
+                    .spawn_bundle(PbrBundle {
+                        mesh: meshes.add(Mesh::from(shape::Box {
+                            min_x,
+                            max_x,
+                            min_y,
+                            max_y,
+                            min_z,
+                            max_z,
+                        })),
+                        material: part_materials[non_zero - 1].clone(),
+                        ..default()
+                    })
+                    .insert(RaycastableGizmo::default())
+                    .insert(axis)
+                    .insert(layers);
+            }
+        }
+    }
+
+    for (text, normal, up) in FACES {
+        // Slightly in front of the face, so that it is not hidden by it
+        let position = normal * (HALF_SIZE + 0.01);
+        commands
+            .spawn_bundle(GizmoLabelBundle {
+                label: GizmoLabel {
+                    height: 0.2,
+                    ..GizmoLabel::new(text)
+                },
+                transform: Transform::from_translation(position).looking_at(position - normal, up),
+                ..default()
+            })
+            .insert(layers);
+    }
+}
+
+/// View cube made of 6 faces, 12 edges and 8 corners, each clickable. Faces report the matching
+/// signed axis, edges and corners a [GizmoClickableAxis::Direction].
+/// Faces are labeled once a [GizmoFont][crate::GizmoFont] is available.
+pub static VIEW_CUBE_GIZMO: crate::GizmoFn = view_cube;